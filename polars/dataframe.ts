@@ -15,6 +15,10 @@ export class DataFrame extends pli.DataFrame {
     return DataFrame.wrap((df as any).ptr);
   }
 
+  static override transfer_in(buf: Uint8Array): DataFrame {
+    return DataFrame.__wrap_ptr(pli.DataFrame.transfer_in(buf));
+  }
+
   override lazy(): LazyFrame {
     return LazyFrame.__wrap_ptr(super.lazy());
   }
@@ -1,4 +1,4 @@
-import { DataFrame, POLARS_WORKER } from "./index.js";
+import { DataFrame, LazyFrame, POLARS_WORKER } from "./index.js";
 import { waitForMsgType } from "./utils.js";
 
 const readCsvDefaultOptions = {
@@ -13,56 +13,557 @@ const readCsvDefaultOptions = {
   lowMemory: false,
   parseDates: false,
   skipRowsAfterHeader: 0,
+  quoteChar: '"',
+  eolChar: "\n",
+  rowCountOffset: 0,
 };
 
 export interface ReadCsvOptions {
   inferSchemaLength?: number;
+  /** Defaults to `true`, or to the sniffed header with `sep: "auto"`. */
   hasHeader?: boolean;
   ignoreErrors?: boolean;
   chunkSize?: number;
   skipRows?: number;
+  /** Field delimiter, `auto` detects it together with quoting, header and line endings. */
   sep?: string;
   rechunk?: boolean;
+  /**
+   * `utf8`, `utf8-lossy`, `auto` or an encoding label such as `windows-1252` or `utf-16le`.
+   * Defaults to `utf8`, or to the sniffed encoding with `sep: "auto"`.
+   */
   encoding?: string;
   lowMemory?: boolean;
   parseDates?: boolean;
   skipRowsAfterHeader?: number;
   numRows?: number;
   numThreads?: number;
+  /**
+   * Single byte quote character, `null` disables quoting.
+   * Defaults to `"`, or to the sniffed quote with `sep: "auto"`.
+   */
+  quoteChar?: string | null;
+  /** Lines starting with this character are skipped. */
+  commentChar?: string;
+  /** Defaults to `\n`, or to the sniffed line ending with `sep: "auto"`. */
+  eolChar?: string;
+  /** A marker for all columns, a list of markers, or an object of `{column: marker}`. */
+  nullValues?: string | string[] | Record<string, string>;
+  /** Overwrite the inferred dtypes, e.g. `{ "a": "Int32" }`. */
+  dtypes?: Record<string, string>;
+  columns?: string[];
+  projection?: number[];
+  rowCountName?: string;
+  rowCountOffset?: number;
+  /** One of `infer`, `uncompressed`, `gzip`, `zlib`, `deflate` or `zstd`. */
+  compression?: string;
+}
+
+export interface ReadParquetOptions {
+  columns?: string[];
+  projection?: number[];
+  numRows?: number;
+  /** One of `auto`, `columns`, `row_groups` or `none`. */
+  parallel?: string;
+  rowCountName?: string;
+  rowCountOffset?: number;
+  /** One of `infer`, `uncompressed`, `gzip`, `zlib`, `deflate` or `zstd`. */
+  compression?: string;
+}
+
+export interface ReadIpcOptions {
+  columns?: string[];
+  projection?: number[];
+  numRows?: number;
+  rowCountName?: string;
+  rowCountOffset?: number;
+  /** One of `infer`, `uncompressed`, `gzip`, `zlib`, `deflate` or `zstd`. */
+  compression?: string;
+}
+
+export interface ReadJsonOptions {
+  inferSchemaLength?: number;
+  batchSize?: number;
+  /** Explicit dtypes, e.g. `{ "a": "Int32" }`. */
+  schema?: Record<string, string>;
+  /** One of `infer`, `uncompressed`, `gzip`, `zlib`, `deflate` or `zstd`. */
+  compression?: string;
+}
+
+export interface ReadNdjsonOptions extends ReadJsonOptions {
+  numRows?: number;
+  lowMemory?: boolean;
+  rechunk?: boolean;
+}
+
+export interface ReadAvroOptions {
+  columns?: string[];
+  projection?: number[];
+  numRows?: number;
+  /** One of `infer`, `uncompressed`, `gzip`, `zlib`, `deflate` or `zstd`. */
+  compression?: string;
+}
+
+export interface ScanCsvOptions {
+  inferSchemaLength?: number;
+  hasHeader?: boolean;
+  ignoreErrors?: boolean;
+  numRows?: number;
+  skipRows?: number;
+  parseDates?: boolean;
+  sep?: string;
+  quoteChar?: string | null;
+  commentChar?: string;
+  /** Defaults to `\n`, or to the sniffed line ending with `sep: "auto"`. */
+  eolChar?: string;
+  nullValues?: string | string[] | Record<string, string>;
+  dtypes?: Record<string, string>;
+  compression?: string;
+  /** `utf8`, `utf8-lossy` or an encoding label such as `windows-1252` or `utf-16le`. */
+  encoding?: string;
+  /** Add the `key=value` directories of registered file paths as columns. */
+  hivePartitioning?: boolean;
+}
+
+export interface ReadCsvManyOptions {
+  /**
+   * `vertical` if all files have the same columns,
+   * `diagonal` to match columns by name and fill missing columns with nulls.
+   */
+  how?: "vertical" | "diagonal";
+  /** Name of a column holding the index of the file every row comes from. */
+  sourceColumn?: string;
+  inferSchemaLength?: number;
+  hasHeader?: boolean;
+  ignoreErrors?: boolean;
+  parseDates?: boolean;
+  sep?: string;
+  quoteChar?: string | null;
+  eolChar?: string;
+  nullValues?: string | string[] | Record<string, string>;
+  rechunk?: boolean;
+  compression?: string;
+  encoding?: string;
+}
+
+/**
+ * Register a file in the in-memory filesystem of the worker, replacing any file at the same path.
+ * Registered paths can be passed to `scan_csv` and the `read_*` functions, and used in globs.
+ * The bytes are transferred to the worker, so `bytes` can't be used afterwards.
+ */
+export async function register_file(
+  path: string,
+  bytes: Uint8Array,
+): Promise<void> {
+  POLARS_WORKER.postMessage({ type: "register_file", path, bytes }, [
+    bytes.buffer,
+  ]);
+  await waitForMsgType(POLARS_WORKER, "register_file");
+}
+
+/**
+ * Remove a file from the in-memory filesystem.
+ * @returns whether a file was registered at `path`
+ */
+export async function unregister_file(path: string): Promise<boolean> {
+  POLARS_WORKER.postMessage({ type: "unregister_file", path });
+  const event: any = await waitForMsgType(POLARS_WORKER, "unregister_file");
+  return event.data.removed;
+}
+
+/**
+ * List the paths of the registered files matching `pattern`, in sorted order.
+ * @param pattern - a glob such as `data/**\/*.csv`, all files are listed if omitted
+ */
+export async function list_files(pattern?: string): Promise<string[]> {
+  POLARS_WORKER.postMessage({ type: "list_files", pattern });
+  const event: any = await waitForMsgType(POLARS_WORKER, "list_files");
+  return event.data.paths;
+}
+
+function isUrl(path: string): boolean {
+  try {
+    new URL(path);
+    return true;
+  } catch (e) {
+    return false;
+  }
+}
+
+async function toBuffer(
+  path: string | Uint8Array,
+): Promise<Uint8Array | string> {
+  if (typeof path !== "string") {
+    return path;
+  }
+  if (!isUrl(path)) {
+    // not a url, the worker resolves it against the files registered with `register_file`
+    return path;
+  }
+  const url = new URL(path);
+  if (url.protocol === "http:" || url.protocol === "https:") {
+    const response = await fetch(path);
+    let arrayBuffer = await response.arrayBuffer();
+    return new Uint8Array(arrayBuffer);
+  } else {
+    throw new Error("Only http and https protocols are supported");
+  }
+}
+
+function transferList(buf: Uint8Array | string): ArrayBuffer[] {
+  return typeof buf === "string" ? [] : [buf.buffer as ArrayBuffer];
+}
+
+async function runInWorker(
+  type: string,
+  path: string | Uint8Array,
+  options: any,
+): Promise<number> {
+  const buf = await toBuffer(path);
+
+  POLARS_WORKER.postMessage(
+    {
+      type,
+      options,
+      buf,
+    },
+    transferList(buf),
+  );
+  const event: any = await waitForMsgType(POLARS_WORKER, type);
+  return event.data.ptr;
+}
+
+async function readInWorker(
+  type: string,
+  path: string | Uint8Array,
+  options: any,
+): Promise<DataFrame> {
+  const ptr = await runInWorker(type, path, options);
+
+  return DataFrame.wrap(ptr);
 }
 
 export async function read_csv(
   path: string | Uint8Array,
   options: ReadCsvOptions = readCsvDefaultOptions,
 ): Promise<DataFrame> {
-  let buf: Uint8Array;
-  if (typeof path === "string") {
-    try {
-      const url = new URL(path);
-      if (url.protocol === "http:" || url.protocol === "https:") {
-        const response = await fetch(path);
-        let arrayBuffer = await response.arrayBuffer();
-        buf = new Uint8Array(arrayBuffer);
-      } else {
-        throw new Error("Only http and https protocols are supported");
-      }
-    } catch (e) {
-      throw new Error(`${path} is not a valid url`);
-    }
+  return readInWorker("read_csv", path, options);
+}
+
+export async function read_parquet(
+  path: string | Uint8Array,
+  options: ReadParquetOptions = {},
+): Promise<DataFrame> {
+  return readInWorker("read_parquet", path, options);
+}
+
+export async function read_ipc(
+  path: string | Uint8Array,
+  options: ReadIpcOptions = {},
+): Promise<DataFrame> {
+  return readInWorker("read_ipc", path, options);
+}
+
+export async function read_json(
+  path: string | Uint8Array,
+  options: ReadJsonOptions = {},
+): Promise<DataFrame> {
+  return readInWorker("read_json", path, options);
+}
+
+export async function read_ndjson(
+  path: string | Uint8Array,
+  options: ReadNdjsonOptions = {},
+): Promise<DataFrame> {
+  return readInWorker("read_ndjson", path, options);
+}
+
+export async function read_avro(
+  path: string | Uint8Array,
+  options: ReadAvroOptions = {},
+): Promise<DataFrame> {
+  return readInWorker("read_avro", path, options);
+}
+
+/** Read many CSV files into one DataFrame, parsing them in parallel. */
+export async function read_csv_many(
+  paths: (string | Uint8Array)[],
+  options: ReadCsvManyOptions = {},
+): Promise<DataFrame> {
+  const bufs = await Promise.all(paths.map(toBuffer));
+
+  POLARS_WORKER.postMessage(
+    {
+      type: "read_csv_many",
+      options,
+      bufs,
+    },
+    bufs.flatMap(transferList),
+  );
+  const event: any = await waitForMsgType(POLARS_WORKER, "read_csv_many");
+  return DataFrame.wrap(event.data.ptr);
+}
+
+export async function scan_csv(
+  path: string | Uint8Array,
+  options: ScanCsvOptions = {},
+): Promise<LazyFrame> {
+  if (typeof path === "string" && !isUrl(path)) {
+    // registered files are scanned in place, paths may be globs
+    POLARS_WORKER.postMessage({ type: "scan_csv_path", path, options });
+    const event: any = await waitForMsgType(POLARS_WORKER, "scan_csv_path");
+    return LazyFrame.wrap(event.data.ptr);
+  }
+  const ptr = await runInWorker("scan_csv", path, options);
+
+  return LazyFrame.wrap(ptr);
+}
+
+/** Random access to the bytes of a file, e.g. on top of HTTP range requests. */
+export interface ByteRangeSource {
+  size(): number | Promise<number>;
+  read(offset: number, length: number): Uint8Array | Promise<Uint8Array>;
+}
+
+/** Bytes of a source handed to the worker per read. */
+const RANGE_CHUNK_BYTES = 1 << 20;
+
+let nextRangeSourceId = 0;
+
+/**
+ * Lazily read a Parquet file. Only the footer is read up front, the row groups
+ * and columns the query needs are read when it is collected.
+ *
+ * A URL is read with range requests made by the worker. Any other source stays on
+ * this thread and answers the reads of the worker through shared memory, so `read`
+ * may be asynchronous, e.g. a `fetch` or a `Blob.slice`.
+ * @param source - a URL whose server supports `Range` requests, or an object
+ * with `size()` and `read(offset, length)` methods
+ * @example
+ * ```js
+ * > const source = {
+ * ...   size: () => bytes.length,
+ * ...   read: (offset, length) => bytes.subarray(offset, offset + length),
+ * ... }
+ * > const lf = await pl.scan_parquet(source)
+ * > await lf.filter(pl.col("year").gt(2020)).select([pl.col("sales")]).collect()
+ * ```
+ */
+export async function scan_parquet(
+  source: string | ByteRangeSource,
+): Promise<LazyFrame> {
+  if (typeof source === "string") {
+    POLARS_WORKER.postMessage({ type: "scan_parquet", url: source });
   } else {
-    buf = path;
+    const id = nextRangeSourceId++;
+    const size = await source.size();
+    // `[done, length]` followed by the bytes of the chunk
+    const shared = new SharedArrayBuffer(8 + RANGE_CHUNK_BYTES);
+    const state = new Int32Array(shared, 0, 2);
+    const chunk = new Uint8Array(shared, 8);
+    POLARS_WORKER.addEventListener("message", async (event: any) => {
+      const { data } = event;
+      if (data?.type !== "scan_parquet_read" || data.id !== id) return;
+      try {
+        const bytes = await source.read(data.offset, data.length);
+        const n = Math.min(bytes.length, data.length, RANGE_CHUNK_BYTES);
+        chunk.set(bytes.subarray(0, n));
+        state[1] = n;
+      } catch (e) {
+        console.error(e);
+        state[1] = -1;
+      }
+      Atomics.store(state, 0, 1);
+      Atomics.notify(state, 0);
+    });
+    POLARS_WORKER.postMessage({ type: "scan_parquet", id, size, shared });
+  }
+  const event: any = await waitForMsgType(POLARS_WORKER, "scan_parquet");
+  return LazyFrame.wrap(event.data.ptr);
+}
+
+export interface CsvBatchReaderOptions {
+  /** Rows per batch, defaults to `50000`. */
+  batchSize?: number;
+  hasHeader?: boolean;
+  sep?: string;
+  /** Single byte quote character, `null` disables quoting. */
+  quoteChar?: string | null;
+  eolChar?: string;
+  inferSchemaLength?: number;
+  ignoreErrors?: boolean;
+  /** One of `infer`, `uncompressed`, `gzip`, `zlib`, `deflate` or `zstd`. */
+  compression?: string;
+  /** `utf8`, `utf8-lossy` or an encoding label such as `windows-1252` or `utf-16le`. */
+  encoding?: string;
+}
+
+let nextCsvBatchReaderId = 0;
+
+/**
+ * Read a CSV file chunk by chunk, e.g. from `File.stream()`, parsing it in the worker.
+ *
+ * Incomplete lines are carried over to the next chunk. The schema is inferred
+ * from the first chunk that holds data rows and is used for all following batches.
+ * Chunks are transferred to the worker, so they can't be used after `push`.
+ * @example
+ * ```js
+ * > const reader = await pl.CsvBatchReader.create({ batchSize: 50000 })
+ * > for await (const chunk of file.stream()) {
+ * ...   for (const df of await reader.push(chunk)) {
+ * ...     console.log(df.height())
+ * ...   }
+ * ... }
+ * > await reader.finish()
+ * ```
+ */
+export class CsvBatchReader {
+  private seq = 0;
+  /** The schema locked after the first batch, as an object of `{column: dtype}`. */
+  schema: Record<string, string> | null = null;
+
+  private constructor(private id: number) {}
+
+  static async create(
+    options: CsvBatchReaderOptions = {},
+  ): Promise<CsvBatchReader> {
+    const reader = new CsvBatchReader(nextCsvBatchReaderId++);
+    await reader.request("new", { options });
+    return reader;
+  }
+
+  /**
+   * Push the next chunk of bytes.
+   * @returns every full batch of `batchSize` rows that could be completed
+   */
+  async push(chunk: Uint8Array): Promise<DataFrame[]> {
+    return this.request("push", { chunk }, [chunk.buffer as ArrayBuffer]);
+  }
+
+  /**
+   * Parse the remaining bytes that are not followed by a line terminator.
+   * The reader can't be used afterwards.
+   * @returns the remaining batches, the last one may hold less than `batchSize` rows
+   */
+  async finish(): Promise<DataFrame[]> {
+    return this.request("finish", {});
   }
 
+  private async request(
+    method: string,
+    data: object,
+    transfer: ArrayBuffer[] = [],
+  ): Promise<DataFrame[]> {
+    const type = `CsvBatchReader:${this.id}:${this.seq++}`;
+    POLARS_WORKER.postMessage(
+      { type: "CsvBatchReader", method, id: this.id, reply: type, ...data },
+      transfer,
+    );
+    const event: any = await waitForMsgType(POLARS_WORKER, type);
+    this.schema = event.data.schema ?? null;
+    return (event.data.ptrs ?? []).map((ptr: number) => DataFrame.wrap(ptr));
+  }
+}
+
+export interface CsvDialect {
+  sep: string;
+  quoteChar: string | null;
+  hasHeader: boolean;
+  eolChar: string;
+  encoding: string;
+  schema: Record<string, string>;
+}
+
+export async function sniff_csv(
+  path: string | Uint8Array,
+  sampleBytes?: number,
+  compression: string = "infer",
+): Promise<CsvDialect> {
+  const buf = await toBuffer(path);
+
+  POLARS_WORKER.postMessage(
+    {
+      type: "sniff_csv",
+      options: { sampleBytes, compression },
+      buf,
+    },
+    transferList(buf),
+  );
+  const event: any = await waitForMsgType(POLARS_WORKER, "sniff_csv");
+  return event.data.dialect;
+}
+
+export interface FileSchema {
+  schema: Record<string, string>;
+  /** `null` when the row count isn't known without parsing the file. */
+  numRows: number | null;
+}
+
+export interface ParquetColumnStatistics {
+  nullCount: number | null;
+  min: any;
+  max: any;
+}
+
+export interface ParquetRowGroup {
+  numRows: number;
+  totalByteSize: number;
+  columns: Record<string, ParquetColumnStatistics>;
+}
+
+export interface ParquetMetadata extends FileSchema {
+  rowGroups: ParquetRowGroup[];
+}
+
+export interface ReadCsvSchemaOptions {
+  inferSchemaLength?: number;
+  hasHeader?: boolean;
+  sep?: string;
+  quoteChar?: string | null;
+  eolChar?: string;
+  compression?: string;
+  encoding?: string;
+}
+
+async function inspectInWorker(
+  type: string,
+  path: string | Uint8Array,
+  options: any,
+): Promise<any> {
+  const buf = await toBuffer(path);
+
   POLARS_WORKER.postMessage(
     {
-      type: "read_csv",
+      type,
       options,
       buf,
     },
-    [buf.buffer],
+    transferList(buf),
   );
-  const event: any = await waitForMsgType(POLARS_WORKER, "read_csv");
-  const ptr = event.data.ptr;
+  const event: any = await waitForMsgType(POLARS_WORKER, type);
+  return event.data.info;
+}
 
-  return DataFrame.wrap(ptr);
+/** Infer the columns and dtypes of a CSV file from its first rows. */
+export function read_csv_schema(
+  path: string | Uint8Array,
+  options: ReadCsvSchemaOptions = {},
+): Promise<FileSchema> {
+  return inspectInWorker("read_csv_schema", path, options);
+}
+
+/** Read the schema, row count and row group statistics of a Parquet file. */
+export function read_parquet_metadata(
+  path: string | Uint8Array,
+  compression: string = "infer",
+): Promise<ParquetMetadata> {
+  return inspectInWorker("read_parquet_metadata", path, { compression });
+}
+
+/** Read the schema and row count of an Arrow IPC file. */
+export function read_ipc_schema(
+  path: string | Uint8Array,
+  compression: string = "infer",
+): Promise<FileSchema> {
+  return inspectInWorker("read_ipc_schema", path, { compression });
 }
@@ -2,6 +2,26 @@ import { DataFrame, POLARS_WORKER } from "../../index.js";
 import * as pli from "../../core/browser.js";
 import { waitForMsgType } from "../../utils.js";
 
+export interface SinkCsvOptions {
+  hasHeader?: boolean;
+  sep?: string;
+  quote?: string;
+  quoteStyle?: "necessary" | "always" | "non_numeric";
+  nullValue?: string;
+  batchSize?: number;
+}
+
+export interface SinkParquetOptions {
+  compression?: "uncompressed" | "snappy" | "lz4" | "zstd";
+  compressionLevel?: number;
+  statistics?: boolean;
+  rowGroupSize?: number;
+}
+
+export interface SinkIpcOptions {
+  compression?: "uncompressed" | "lz4" | "zstd";
+}
+
 export class LazyFrame extends pli.LazyFrame {
   private ptr!: number;
 
@@ -30,6 +50,58 @@ export class LazyFrame extends pli.LazyFrame {
     return DataFrame.wrap(df_ptr);
   }
 
+  /**
+   * Run the query in the worker and stream its result as CSV,
+   * calling `onChunk` with every encoded chunk.
+   */
+  sink_csv(
+    onChunk: (chunk: Uint8Array) => void,
+    options: SinkCsvOptions = {},
+  ): Promise<void> {
+    return this.sink("LazyFrame::sink_csv", onChunk, options);
+  }
+
+  /**
+   * Run the query in the worker and stream its result as Parquet,
+   * calling `onChunk` with every encoded chunk.
+   */
+  sink_parquet(
+    onChunk: (chunk: Uint8Array) => void,
+    options: SinkParquetOptions = {},
+  ): Promise<void> {
+    return this.sink("LazyFrame::sink_parquet", onChunk, options);
+  }
+
+  /**
+   * Run the query in the worker and stream its result as Arrow IPC,
+   * calling `onChunk` with every encoded chunk.
+   */
+  sink_ipc(
+    onChunk: (chunk: Uint8Array) => void,
+    options: SinkIpcOptions = {},
+  ): Promise<void> {
+    return this.sink("LazyFrame::sink_ipc", onChunk, options);
+  }
+
+  private async sink(
+    type: string,
+    onChunk: (chunk: Uint8Array) => void,
+    options: any,
+  ): Promise<void> {
+    const onMsg = (event: any) => {
+      if (event.data?.type === `${type}::chunk`) {
+        onChunk(event.data.chunk);
+      }
+    };
+    POLARS_WORKER.addEventListener("message", onMsg);
+    try {
+      POLARS_WORKER.postMessage({ type, ptr: this.ptr, options });
+      await waitForMsgType(POLARS_WORKER, type);
+    } finally {
+      POLARS_WORKER.removeEventListener("message", onMsg);
+    }
+  }
+
   override select(exprs: pli.Expr[]): LazyFrame {
     return LazyFrame.__wrap_ptr(super.select(exprs));
   }
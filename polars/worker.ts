@@ -4,7 +4,22 @@
  * any function that uses the rust threadpool needs to be executed in the worker.
  */
 import * as pli from "./core/browser.js";
-import { ReadCsvOptions } from "./io.js";
+import {
+  ReadAvroOptions,
+  ReadCsvManyOptions,
+  ReadCsvOptions,
+  ReadCsvSchemaOptions,
+  ReadIpcOptions,
+  ReadJsonOptions,
+  ReadNdjsonOptions,
+  ReadParquetOptions,
+  ScanCsvOptions,
+} from "./io.js";
+import {
+  SinkCsvOptions,
+  SinkIpcOptions,
+  SinkParquetOptions,
+} from "./lazy/frame/index.js";
 
 let initialized = false;
 
@@ -17,22 +32,35 @@ export async function start(mem: WebAssembly.Memory) {
 }
 
 
-function read_csv(buf: Uint8Array, options: ReadCsvOptions): void {
+function read_csv(buf: Uint8Array | string, options: ReadCsvOptions): void {
   const ptr = (
     pli.read_csv(
       buf,
       options.inferSchemaLength ?? 100,
       options.chunkSize ?? 10000,
-      options.hasHeader ?? true,
+      // `hasHeader`, `encoding`, `quoteChar` and `eolChar` are left unset
+      // so that `sep: "auto"` can use the sniffed dialect
+      options.hasHeader,
       options.ignoreErrors ?? true,
       options.numRows,
       options.skipRows ?? 0,
       options.rechunk ?? false,
-      options.encoding ?? "utf8",
+      options.encoding,
       options.numThreads,
       options.lowMemory ?? false,
       options.parseDates ?? false,
       options.skipRowsAfterHeader ?? 0,
+      options.sep ?? ",",
+      options.quoteChar,
+      options.commentChar,
+      options.eolChar,
+      options.nullValues,
+      options.dtypes,
+      options.columns,
+      options.projection ? new Uint32Array(options.projection) : undefined,
+      options.rowCountName,
+      options.rowCountOffset ?? 0,
+      options.compression ?? "infer",
     ) as any
   ).ptr;
   return postMessage({
@@ -41,6 +69,415 @@ function read_csv(buf: Uint8Array, options: ReadCsvOptions): void {
   });
 }
 
+function read_parquet(
+  buf: Uint8Array | string,
+  options: ReadParquetOptions,
+): void {
+  const ptr = (
+    pli.read_parquet(
+      buf,
+      options.columns,
+      options.projection ? new Uint32Array(options.projection) : undefined,
+      options.numRows,
+      options.parallel ?? "auto",
+      options.rowCountName,
+      options.rowCountOffset ?? 0,
+      options.compression ?? "infer",
+    ) as any
+  ).ptr;
+  return postMessage({
+    type: "read_parquet",
+    ptr,
+  });
+}
+
+function read_ipc(buf: Uint8Array | string, options: ReadIpcOptions): void {
+  const ptr = (
+    pli.read_ipc(
+      buf,
+      options.columns,
+      options.projection ? new Uint32Array(options.projection) : undefined,
+      options.numRows,
+      options.rowCountName,
+      options.rowCountOffset ?? 0,
+      options.compression ?? "infer",
+    ) as any
+  ).ptr;
+  return postMessage({
+    type: "read_ipc",
+    ptr,
+  });
+}
+
+function read_json(buf: Uint8Array | string, options: ReadJsonOptions): void {
+  const ptr = (
+    pli.read_json(
+      buf,
+      options.inferSchemaLength ?? 100,
+      options.batchSize ?? 10000,
+      options.schema,
+      options.compression ?? "infer",
+    ) as any
+  ).ptr;
+  return postMessage({
+    type: "read_json",
+    ptr,
+  });
+}
+
+function read_ndjson(
+  buf: Uint8Array | string,
+  options: ReadNdjsonOptions,
+): void {
+  const ptr = (
+    pli.read_ndjson(
+      buf,
+      options.inferSchemaLength ?? 100,
+      options.batchSize ?? 10000,
+      options.numRows,
+      options.lowMemory ?? false,
+      options.rechunk ?? false,
+      options.schema,
+      options.compression ?? "infer",
+    ) as any
+  ).ptr;
+  return postMessage({
+    type: "read_ndjson",
+    ptr,
+  });
+}
+
+function read_avro(buf: Uint8Array | string, options: ReadAvroOptions): void {
+  const ptr = (
+    pli.read_avro(
+      buf,
+      options.columns,
+      options.projection ? new Uint32Array(options.projection) : undefined,
+      options.numRows,
+      options.compression ?? "infer",
+    ) as any
+  ).ptr;
+  return postMessage({
+    type: "read_avro",
+    ptr,
+  });
+}
+
+function read_csv_many(
+  bufs: (Uint8Array | string)[],
+  options: ReadCsvManyOptions,
+): void {
+  const ptr = (
+    pli.read_csv_many(
+      bufs,
+      options.how ?? "vertical",
+      options.sourceColumn,
+      options.inferSchemaLength ?? 100,
+      options.hasHeader ?? true,
+      options.ignoreErrors ?? true,
+      options.parseDates ?? false,
+      options.sep ?? ",",
+      options.quoteChar === undefined ? '"' : options.quoteChar ?? undefined,
+      options.eolChar ?? "\n",
+      options.nullValues,
+      options.rechunk ?? false,
+      options.compression ?? "infer",
+      options.encoding ?? "utf8",
+    ) as any
+  ).ptr;
+  return postMessage({
+    type: "read_csv_many",
+    ptr,
+  });
+}
+
+function scan_csv(buf: Uint8Array | string, options: ScanCsvOptions): void {
+  const ptr = (
+    pli.scan_csv(
+      buf,
+      options.inferSchemaLength ?? 100,
+      options.hasHeader ?? true,
+      options.ignoreErrors ?? true,
+      options.numRows,
+      options.skipRows ?? 0,
+      options.parseDates ?? false,
+      options.sep ?? ",",
+      options.quoteChar === undefined ? '"' : options.quoteChar ?? undefined,
+      options.commentChar,
+      options.eolChar ?? "\n",
+      options.nullValues,
+      options.dtypes,
+      options.compression ?? "infer",
+      options.encoding ?? "utf8",
+    ) as any
+  ).ptr;
+  return postMessage({
+    type: "scan_csv",
+    ptr,
+  });
+}
+
+function scan_csv_path(path: string, options: ScanCsvOptions): void {
+  const ptr = (
+    pli.scan_csv_path(
+      path,
+      options.hivePartitioning ?? true,
+      options.inferSchemaLength ?? 100,
+      options.hasHeader ?? true,
+      options.ignoreErrors ?? true,
+      options.numRows,
+      options.skipRows ?? 0,
+      options.parseDates ?? false,
+      options.sep ?? ",",
+      options.quoteChar === undefined ? '"' : options.quoteChar ?? undefined,
+      options.commentChar,
+      options.eolChar ?? "\n",
+      options.nullValues,
+      options.dtypes,
+      options.compression ?? "infer",
+      options.encoding ?? "utf8",
+    ) as any
+  ).ptr;
+  return postMessage({
+    type: "scan_csv_path",
+    ptr,
+  });
+}
+
+/** A source reading `url` with synchronous range requests, which only workers may make. */
+function urlSource(url: string) {
+  const get = (start: number, end: number) => {
+    const xhr = new XMLHttpRequest();
+    xhr.open("GET", url, false);
+    xhr.responseType = "arraybuffer";
+    xhr.setRequestHeader("Range", `bytes=${start}-${end}`);
+    xhr.send();
+    if (xhr.status === 200) {
+      throw new Error(`${url} doesn't support range requests`);
+    }
+    if (xhr.status !== 206) {
+      throw new Error(`request to ${url} failed with status ${xhr.status}`);
+    }
+    return xhr;
+  };
+  const range = get(0, 0).getResponseHeader("Content-Range");
+  const size = Number(range?.split("/")[1]);
+  if (!Number.isFinite(size)) {
+    throw new Error(`${url} didn't report the size of the file`);
+  }
+  return {
+    size: () => size,
+    read: (offset: number, length: number) =>
+      new Uint8Array(get(offset, offset + length - 1).response),
+  };
+}
+
+/**
+ * A source whose reads are answered by the main thread, see `scan_parquet` in io.ts.
+ * Every read blocks until the main thread stored the requested chunk in `shared`.
+ */
+function sharedSource(id: number, size: number, shared: SharedArrayBuffer) {
+  const state = new Int32Array(shared, 0, 2);
+  const chunk = new Uint8Array(shared, 8);
+  return {
+    size: () => size,
+    read: (offset: number, length: number) => {
+      const out = new Uint8Array(length);
+      let filled = 0;
+      while (filled < length) {
+        const n = Math.min(length - filled, chunk.length);
+        Atomics.store(state, 0, 0);
+        postMessage({
+          type: "scan_parquet_read",
+          id,
+          offset: offset + filled,
+          length: n,
+        });
+        Atomics.wait(state, 0, 0);
+        const read = state[1];
+        if (read < 0) {
+          throw new Error("reading the Parquet source failed");
+        }
+        out.set(chunk.subarray(0, read), filled);
+        filled += read;
+        if (read < n) break;
+      }
+      return out.subarray(0, filled);
+    },
+  };
+}
+
+function scan_parquet(data: any): void {
+  const source =
+    data.url !== undefined
+      ? urlSource(data.url)
+      : sharedSource(data.id, data.size, data.shared);
+  const ptr = (pli as any).__scan_parquet_from_worker(source).ptr;
+  return postMessage({
+    type: "scan_parquet",
+    ptr,
+  });
+}
+
+function register_file(path: string, bytes: Uint8Array): void {
+  (pli as any).__register_file_from_worker(path, bytes);
+  return postMessage({ type: "register_file" });
+}
+
+function unregister_file(path: string): void {
+  const removed = (pli as any).__unregister_file_from_worker(path);
+  return postMessage({ type: "unregister_file", removed });
+}
+
+function list_files(pattern?: string): void {
+  const paths = (pli as any).__list_files_from_worker(pattern);
+  return postMessage({ type: "list_files", paths });
+}
+
+/** The `CsvBatchReader`s of the main thread, by id. */
+const csvBatchReaders = new Map<number, pli.CsvBatchReader>();
+
+function csv_batch_reader(data: any): void {
+  const { id, reply } = data;
+  switch (data.method) {
+    case "new": {
+      const { options } = data;
+      csvBatchReaders.set(
+        id,
+        new pli.CsvBatchReader(
+          options.batchSize ?? 50000,
+          options.hasHeader ?? true,
+          options.sep ?? ",",
+          options.quoteChar === undefined ? '"' : options.quoteChar ?? undefined,
+          options.eolChar ?? "\n",
+          options.inferSchemaLength ?? 100,
+          options.ignoreErrors ?? false,
+          options.compression ?? "infer",
+          options.encoding ?? "utf8",
+        ),
+      );
+      return postMessage({ type: reply });
+    }
+    case "push": {
+      const reader = csvBatchReaders.get(id)!;
+      const batches = reader.push(data.chunk);
+      return postMessage({
+        type: reply,
+        ptrs: batches.map((df: any) => df.ptr),
+        schema: reader.schema,
+      });
+    }
+    case "finish": {
+      const reader = csvBatchReaders.get(id)!;
+      csvBatchReaders.delete(id);
+      const batches = reader.finish();
+      const schema = reader.schema;
+      reader.free();
+      return postMessage({
+        type: reply,
+        ptrs: batches.map((df: any) => df.ptr),
+        schema,
+      });
+    }
+  }
+}
+
+function sniff_csv(
+  buf: Uint8Array | string,
+  options: { sampleBytes?: number; compression: string },
+): void {
+  const dialect = pli.sniff_csv(buf, options.sampleBytes, options.compression);
+  return postMessage({
+    type: "sniff_csv",
+    dialect,
+  });
+}
+
+function read_csv_schema(
+  buf: Uint8Array | string,
+  options: ReadCsvSchemaOptions,
+): void {
+  const info = pli.read_csv_schema(
+    buf,
+    options.inferSchemaLength ?? 100,
+    options.hasHeader ?? true,
+    options.sep ?? ",",
+    options.quoteChar === undefined ? '"' : options.quoteChar ?? undefined,
+    options.eolChar ?? "\n",
+    options.compression ?? "infer",
+    options.encoding ?? "utf8",
+  );
+  return postMessage({
+    type: "read_csv_schema",
+    info,
+  });
+}
+
+function read_parquet_metadata(
+  buf: Uint8Array | string,
+  options: { compression?: string },
+): void {
+  const info = pli.read_parquet_metadata(buf, options.compression ?? "infer");
+  return postMessage({
+    type: "read_parquet_metadata",
+    info,
+  });
+}
+
+function read_ipc_schema(
+  buf: Uint8Array | string,
+  options: { compression?: string },
+): void {
+  const info = pli.read_ipc_schema(buf, options.compression ?? "infer");
+  return postMessage({
+    type: "read_ipc_schema",
+    info,
+  });
+}
+
+function postSinkChunk(type: string) {
+  return (chunk: Uint8Array) =>
+    postMessage({ type: `${type}::chunk`, chunk }, [chunk.buffer] as any);
+}
+
+function sink_csv(ptr: number, options: SinkCsvOptions): void {
+  const type = "LazyFrame::sink_csv";
+  const lf = (pli.LazyFrame as any).__wrap(ptr);
+  lf.__sink_csv_from_worker(
+    postSinkChunk(type),
+    options.hasHeader ?? true,
+    options.sep ?? ",",
+    options.quote ?? '"',
+    options.quoteStyle ?? "necessary",
+    options.nullValue ?? "",
+    options.batchSize ?? 1024,
+  );
+  return postMessage({ type });
+}
+
+function sink_parquet(ptr: number, options: SinkParquetOptions): void {
+  const type = "LazyFrame::sink_parquet";
+  const lf = (pli.LazyFrame as any).__wrap(ptr);
+  lf.__sink_parquet_from_worker(
+    postSinkChunk(type),
+    options.compression ?? "zstd",
+    options.compressionLevel,
+    options.statistics ?? false,
+    options.rowGroupSize,
+  );
+  return postMessage({ type });
+}
+
+function sink_ipc(ptr: number, options: SinkIpcOptions): void {
+  const type = "LazyFrame::sink_ipc";
+  const lf = (pli.LazyFrame as any).__wrap(ptr);
+  lf.__sink_ipc_from_worker(
+    postSinkChunk(type),
+    options.compression ?? "uncompressed",
+  );
+  return postMessage({ type });
+}
+
 self.addEventListener("message", async (event) => {
   switch (event.data.type) {
     case "start": {
@@ -50,6 +487,69 @@ self.addEventListener("message", async (event) => {
       const { options } = event.data;
       return read_csv(event.data.buf, options);
     }
+    case "read_parquet": {
+      const { options } = event.data;
+      return read_parquet(event.data.buf, options);
+    }
+    case "read_ipc": {
+      const { options } = event.data;
+      return read_ipc(event.data.buf, options);
+    }
+    case "read_json": {
+      const { options } = event.data;
+      return read_json(event.data.buf, options);
+    }
+    case "read_ndjson": {
+      const { options } = event.data;
+      return read_ndjson(event.data.buf, options);
+    }
+    case "read_avro": {
+      const { options } = event.data;
+      return read_avro(event.data.buf, options);
+    }
+    case "read_csv_many": {
+      const { options } = event.data;
+      return read_csv_many(event.data.bufs, options);
+    }
+    case "scan_csv": {
+      const { options } = event.data;
+      return scan_csv(event.data.buf, options);
+    }
+    case "scan_csv_path": {
+      const { options } = event.data;
+      return scan_csv_path(event.data.path, options);
+    }
+    case "scan_parquet": {
+      return scan_parquet(event.data);
+    }
+    case "register_file": {
+      return register_file(event.data.path, event.data.bytes);
+    }
+    case "unregister_file": {
+      return unregister_file(event.data.path);
+    }
+    case "list_files": {
+      return list_files(event.data.pattern);
+    }
+    case "sniff_csv": {
+      const { options } = event.data;
+      return sniff_csv(event.data.buf, options);
+    }
+    case "read_csv_schema": {
+      const { options } = event.data;
+      return read_csv_schema(event.data.buf, options);
+    }
+    case "read_parquet_metadata": {
+      const { options } = event.data;
+      return read_parquet_metadata(event.data.buf, options);
+    }
+    case "read_ipc_schema": {
+      const { options } = event.data;
+      return read_ipc_schema(event.data.buf, options);
+    }
+    case "CsvBatchReader": {
+      return csv_batch_reader(event.data);
+    }
     case "LazyFrame::collect": {
       const lf = (pli.LazyFrame as any).__wrap(event.data.ptr);
       const df = await lf.__collect_from_worker();
@@ -58,6 +558,15 @@ self.addEventListener("message", async (event) => {
         ptr: df.ptr,
       });
     }
+    case "LazyFrame::sink_csv": {
+      return sink_csv(event.data.ptr, event.data.options);
+    }
+    case "LazyFrame::sink_parquet": {
+      return sink_parquet(event.data.ptr, event.data.options);
+    }
+    case "LazyFrame::sink_ipc": {
+      return sink_ipc(event.data.ptr, event.data.options);
+    }
     
     default: {
       console.log("unknown method", event.data.method);
@@ -0,0 +1,517 @@
+use crate::datatypes::dtype_name;
+use crate::{error::JsPolarsErr, JsResult};
+use js_sys::Reflect;
+use polars::export::arrow::array::{
+    Array, BooleanArray, DictionaryArray, ListArray, PrimitiveArray, StructArray, Utf8Array,
+};
+use polars::export::arrow::bitmap::Bitmap;
+use polars::export::arrow::buffer::Buffer;
+use polars::export::arrow::datatypes::{
+    DataType as ArrowDataType, Field as ArrowField, PhysicalType, PrimitiveType,
+    TimeUnit as ArrowTimeUnit,
+};
+use polars::prelude::*;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+fn set(obj: &js_sys::Object, key: &str, val: &JsValue) -> JsResult<()> {
+    Reflect::set(obj, &key.into(), val)?;
+    Ok(())
+}
+
+fn get(obj: &JsValue, key: &str) -> JsResult<JsValue> {
+    Reflect::get(obj, &key.into())
+}
+
+fn unsupported(dtype: &ArrowDataType) -> JsValue {
+    JsPolarsErr::Other(format!("arrow type {:?} is not supported", dtype)).into()
+}
+
+/// The name of an Arrow type in the `type` field of a column description.
+fn type_name(dtype: &ArrowDataType) -> JsResult<&'static str> {
+    let name = match dtype {
+        ArrowDataType::Int8 => "Int8",
+        ArrowDataType::Int16 => "Int16",
+        ArrowDataType::Int32 => "Int32",
+        ArrowDataType::Int64 => "Int64",
+        ArrowDataType::UInt8 => "UInt8",
+        ArrowDataType::UInt16 => "UInt16",
+        ArrowDataType::UInt32 => "UInt32",
+        ArrowDataType::UInt64 => "UInt64",
+        ArrowDataType::Float32 => "Float32",
+        ArrowDataType::Float64 => "Float64",
+        ArrowDataType::Boolean => "Bool",
+        ArrowDataType::Utf8 => "Utf8",
+        ArrowDataType::LargeUtf8 => "LargeUtf8",
+        ArrowDataType::Date32 => "Date32",
+        ArrowDataType::Timestamp(_, _) => "Timestamp",
+        ArrowDataType::Time64(_) => "Time64",
+        ArrowDataType::Duration(_) => "Duration",
+        ArrowDataType::LargeList(_) => "LargeList",
+        ArrowDataType::Struct(_) => "Struct",
+        ArrowDataType::Dictionary(_, _, _) => "Dictionary",
+        dt => return Err(unsupported(dt)),
+    };
+    Ok(name)
+}
+
+fn time_unit_name(tu: &ArrowTimeUnit) -> &'static str {
+    match tu {
+        ArrowTimeUnit::Second => "s",
+        ArrowTimeUnit::Millisecond => "ms",
+        ArrowTimeUnit::Microsecond => "us",
+        ArrowTimeUnit::Nanosecond => "ns",
+    }
+}
+
+fn time_unit_from_js(desc: &JsValue) -> JsResult<ArrowTimeUnit> {
+    match get(desc, "timeUnit")?.as_string().as_deref() {
+        Some("s") => Ok(ArrowTimeUnit::Second),
+        Some("ms") => Ok(ArrowTimeUnit::Millisecond),
+        Some("us") => Ok(ArrowTimeUnit::Microsecond),
+        Some("ns") => Ok(ArrowTimeUnit::Nanosecond),
+        tu => Err(JsPolarsErr::Other(format!(
+            "timeUnit should be one of s, ms, us, ns, got {:?}",
+            tu
+        ))
+        .into()),
+    }
+}
+
+/// A view of the bits of `bitmap` and the bit offset the view starts at.
+unsafe fn bitmap_view(bitmap: &Bitmap) -> (JsValue, usize) {
+    let (bytes, offset, _) = bitmap.as_slice();
+    (js_sys::Uint8Array::view(bytes).into(), offset)
+}
+
+macro_rules! primitive_view {
+    ($arr:expr, $t:ty, $js:ty) => {{
+        let arr = $arr.as_any().downcast_ref::<PrimitiveArray<$t>>().unwrap();
+        <$js>::view(arr.values().as_slice()).into()
+    }};
+}
+
+/// Describe the buffers of `arr` as typed array views into wasm memory.
+///
+/// # Safety
+/// The views are only valid as long as `arr` is alive and wasm memory has not grown.
+unsafe fn array_to_js(arr: &dyn Array) -> JsResult<js_sys::Object> {
+    let obj = js_sys::Object::new();
+    set(&obj, "type", &type_name(arr.data_type())?.into())?;
+    match arr.data_type() {
+        ArrowDataType::Timestamp(tu, tz) => {
+            set(&obj, "timeUnit", &time_unit_name(tu).into())?;
+            let tz = tz.as_deref().map_or(JsValue::null(), JsValue::from_str);
+            set(&obj, "timeZone", &tz)?;
+        }
+        ArrowDataType::Time64(tu) | ArrowDataType::Duration(tu) => {
+            set(&obj, "timeUnit", &time_unit_name(tu).into())?;
+        }
+        _ => {}
+    }
+    set(&obj, "length", &(arr.len() as u32).into())?;
+    set(&obj, "nullCount", &(arr.null_count() as u32).into())?;
+    match arr.validity() {
+        Some(bitmap) => {
+            let (view, offset) = bitmap_view(bitmap);
+            set(&obj, "validity", &view)?;
+            set(&obj, "validityOffset", &(offset as u32).into())?;
+        }
+        None => set(&obj, "validity", &JsValue::null())?,
+    }
+
+    match arr.data_type().to_physical_type() {
+        PhysicalType::Primitive(primitive) => {
+            let data: JsValue = match primitive {
+                PrimitiveType::Int8 => primitive_view!(arr, i8, js_sys::Int8Array),
+                PrimitiveType::Int16 => primitive_view!(arr, i16, js_sys::Int16Array),
+                PrimitiveType::Int32 => primitive_view!(arr, i32, js_sys::Int32Array),
+                PrimitiveType::Int64 => primitive_view!(arr, i64, js_sys::BigInt64Array),
+                PrimitiveType::UInt8 => primitive_view!(arr, u8, js_sys::Uint8Array),
+                PrimitiveType::UInt16 => primitive_view!(arr, u16, js_sys::Uint16Array),
+                PrimitiveType::UInt32 => primitive_view!(arr, u32, js_sys::Uint32Array),
+                PrimitiveType::UInt64 => primitive_view!(arr, u64, js_sys::BigUint64Array),
+                PrimitiveType::Float32 => primitive_view!(arr, f32, js_sys::Float32Array),
+                PrimitiveType::Float64 => primitive_view!(arr, f64, js_sys::Float64Array),
+                _ => return Err(unsupported(arr.data_type())),
+            };
+            set(&obj, "data", &data)?;
+        }
+        PhysicalType::Boolean => {
+            let arr = arr.as_any().downcast_ref::<BooleanArray>().unwrap();
+            let (view, offset) = bitmap_view(arr.values());
+            set(&obj, "data", &view)?;
+            set(&obj, "dataOffset", &(offset as u32).into())?;
+        }
+        PhysicalType::Utf8 => {
+            let arr = arr.as_any().downcast_ref::<Utf8Array<i32>>().unwrap();
+            set(
+                &obj,
+                "offsets",
+                &js_sys::Int32Array::view(arr.offsets().as_slice()).into(),
+            )?;
+            set(
+                &obj,
+                "data",
+                &js_sys::Uint8Array::view(arr.values().as_slice()).into(),
+            )?;
+        }
+        PhysicalType::LargeUtf8 => {
+            let arr = arr.as_any().downcast_ref::<Utf8Array<i64>>().unwrap();
+            set(
+                &obj,
+                "offsets",
+                &js_sys::BigInt64Array::view(arr.offsets().as_slice()).into(),
+            )?;
+            set(
+                &obj,
+                "data",
+                &js_sys::Uint8Array::view(arr.values().as_slice()).into(),
+            )?;
+        }
+        PhysicalType::LargeList => {
+            let arr = arr.as_any().downcast_ref::<ListArray<i64>>().unwrap();
+            set(
+                &obj,
+                "offsets",
+                &js_sys::BigInt64Array::view(arr.offsets().as_slice()).into(),
+            )?;
+            let child = array_to_js(arr.values().as_ref())?;
+            set(&obj, "children", &js_sys::Array::of1(&child).into())?;
+        }
+        PhysicalType::Struct => {
+            let arr = arr.as_any().downcast_ref::<StructArray>().unwrap();
+            let names: js_sys::Array = arr
+                .fields()
+                .iter()
+                .map(|f| JsValue::from_str(&f.name))
+                .collect();
+            let children = js_sys::Array::new();
+            for child in arr.values() {
+                children.push(&array_to_js(child.as_ref())?);
+            }
+            set(&obj, "fields", &names)?;
+            set(&obj, "children", &children)?;
+        }
+        // polars encodes categoricals with u32 keys
+        PhysicalType::Dictionary(_) => {
+            let arr = arr
+                .as_any()
+                .downcast_ref::<DictionaryArray<u32>>()
+                .ok_or_else(|| unsupported(arr.data_type()))?;
+            let keys = arr.keys().values().as_slice();
+            set(&obj, "data", &js_sys::Uint32Array::view(keys).into())?;
+            set(&obj, "dictionary", &array_to_js(arr.values().as_ref())?)?;
+        }
+        _ => return Err(unsupported(arr.data_type())),
+    }
+    Ok(obj)
+}
+
+/// Owns the Arrow arrays of a DataFrame so views into their buffers can be handed to JS.
+#[wasm_bindgen(js_name = ArrowBuffers)]
+pub struct JsArrowBuffers {
+    names: Vec<String>,
+    dtypes: Vec<DataType>,
+    arrays: Vec<ArrayRef>,
+}
+
+impl JsArrowBuffers {
+    /// `df` is expected to be rechunked.
+    pub(crate) fn new(df: &DataFrame) -> Self {
+        JsArrowBuffers {
+            names: df.get_column_names_owned(),
+            dtypes: df.dtypes(),
+            arrays: df.get_columns().iter().map(|s| s.to_arrow(0)).collect(),
+        }
+    }
+}
+
+#[wasm_bindgen(js_class = ArrowBuffers)]
+impl JsArrowBuffers {
+    /// Get the name, dtype and `data`, `offsets` and `validity` buffers of every column.
+    /// `type` is the Arrow type of the buffers, temporal types also have a `timeUnit`
+    /// (`s`, `ms`, `us` or `ns`) and timestamps a `timeZone`.
+    /// Lists and structs describe their values in `children`, dictionaries in `dictionary`.
+    /// The buffers are views into wasm memory, they must not be used after
+    /// this object has been freed or once wasm memory has grown.
+    pub fn columns(&self) -> JsResult<js_sys::Array> {
+        let columns = js_sys::Array::new();
+        for ((name, dtype), arr) in self.names.iter().zip(&self.dtypes).zip(&self.arrays) {
+            let obj = unsafe { array_to_js(arr.as_ref())? };
+            set(&obj, "name", &name.as_str().into())?;
+            set(&obj, "dtype", &dtype_name(dtype).into())?;
+            columns.push(&obj);
+        }
+        Ok(columns)
+    }
+}
+
+macro_rules! typed_array_to_vec {
+    ($data:expr, $js:ty) => {
+        $data
+            .dyn_into::<$js>()
+            .map_err(|_| JsPolarsErr::Other(format!("data should be a {}", stringify!($js))))?
+            .to_vec()
+    };
+}
+
+macro_rules! primitive_from_js {
+    ($data:expr, $validity:expr, $t:ty, $js:ty, $dt:expr) => {{
+        let values: Vec<$t> = typed_array_to_vec!($data, $js);
+        Box::new(
+            PrimitiveArray::<$t>::try_new($dt, values.into(), $validity)
+                .map_err(JsPolarsErr::from)?,
+        ) as ArrayRef
+    }};
+}
+
+/// Copy a bitmap out of a `Uint8Array`, starting at bit `offset`.
+fn bitmap_from_js(bytes: JsValue, offset: usize, length: usize) -> JsResult<Bitmap> {
+    let bytes: Vec<u8> = typed_array_to_vec!(bytes, js_sys::Uint8Array);
+    let bitmap = Bitmap::try_new(bytes, offset + length).map_err(JsPolarsErr::from)?;
+    Ok(bitmap.slice(offset, length))
+}
+
+fn usize_from_js(desc: &JsValue, key: &str) -> JsResult<usize> {
+    get(desc, key)?
+        .as_f64()
+        .map(|n| n as usize)
+        .ok_or_else(|| JsPolarsErr::Other(format!("{} should be a number", key)).into())
+}
+
+fn children_from_js(desc: &JsValue) -> JsResult<Vec<ArrayRef>> {
+    let children = get(desc, "children")?;
+    if !js_sys::Array::is_array(&children) {
+        return Err(JsPolarsErr::Other("children should be an array".into()).into());
+    }
+    js_sys::Array::from(&children)
+        .iter()
+        .map(|child| array_from_js(&child))
+        .collect()
+}
+
+/// Build an Arrow array out of an array description as returned by `ArrowBuffers.columns`.
+/// The buffers are copied into wasm memory.
+fn array_from_js(desc: &JsValue) -> JsResult<ArrayRef> {
+    let ty = get(desc, "type")?
+        .as_string()
+        .ok_or_else(|| JsPolarsErr::Other("type should be a string".into()))?;
+    let length = usize_from_js(desc, "length")?;
+    let validity = get(desc, "validity")?;
+    let validity = if validity.is_null() || validity.is_undefined() {
+        None
+    } else {
+        let offset = get(desc, "validityOffset")?.as_f64().unwrap_or(0.0) as usize;
+        Some(bitmap_from_js(validity, offset, length)?)
+    };
+    let data = get(desc, "data")?;
+
+    let arr = match ty.as_str() {
+        "Int8" => primitive_from_js!(data, validity, i8, js_sys::Int8Array, ArrowDataType::Int8),
+        "Int16" => {
+            primitive_from_js!(
+                data,
+                validity,
+                i16,
+                js_sys::Int16Array,
+                ArrowDataType::Int16
+            )
+        }
+        "Int32" => {
+            primitive_from_js!(
+                data,
+                validity,
+                i32,
+                js_sys::Int32Array,
+                ArrowDataType::Int32
+            )
+        }
+        "Int64" => {
+            primitive_from_js!(
+                data,
+                validity,
+                i64,
+                js_sys::BigInt64Array,
+                ArrowDataType::Int64
+            )
+        }
+        "UInt8" => {
+            primitive_from_js!(data, validity, u8, js_sys::Uint8Array, ArrowDataType::UInt8)
+        }
+        "UInt16" => {
+            primitive_from_js!(
+                data,
+                validity,
+                u16,
+                js_sys::Uint16Array,
+                ArrowDataType::UInt16
+            )
+        }
+        "UInt32" => {
+            primitive_from_js!(
+                data,
+                validity,
+                u32,
+                js_sys::Uint32Array,
+                ArrowDataType::UInt32
+            )
+        }
+        "UInt64" => {
+            primitive_from_js!(
+                data,
+                validity,
+                u64,
+                js_sys::BigUint64Array,
+                ArrowDataType::UInt64
+            )
+        }
+        "Float32" => {
+            primitive_from_js!(
+                data,
+                validity,
+                f32,
+                js_sys::Float32Array,
+                ArrowDataType::Float32
+            )
+        }
+        "Float64" => {
+            primitive_from_js!(
+                data,
+                validity,
+                f64,
+                js_sys::Float64Array,
+                ArrowDataType::Float64
+            )
+        }
+        "Date32" => primitive_from_js!(
+            data,
+            validity,
+            i32,
+            js_sys::Int32Array,
+            ArrowDataType::Date32
+        ),
+        "Timestamp" => {
+            let tz = get(desc, "timeZone")?.as_string();
+            primitive_from_js!(
+                data,
+                validity,
+                i64,
+                js_sys::BigInt64Array,
+                ArrowDataType::Timestamp(time_unit_from_js(desc)?, tz)
+            )
+        }
+        "Time64" => primitive_from_js!(
+            data,
+            validity,
+            i64,
+            js_sys::BigInt64Array,
+            ArrowDataType::Time64(time_unit_from_js(desc)?)
+        ),
+        "Duration" => primitive_from_js!(
+            data,
+            validity,
+            i64,
+            js_sys::BigInt64Array,
+            ArrowDataType::Duration(time_unit_from_js(desc)?)
+        ),
+        "Bool" => {
+            let offset = get(desc, "dataOffset")?.as_f64().unwrap_or(0.0) as usize;
+            let values = bitmap_from_js(data, offset, length)?;
+            Box::new(
+                BooleanArray::try_new(ArrowDataType::Boolean, values, validity)
+                    .map_err(JsPolarsErr::from)?,
+            ) as ArrayRef
+        }
+        "Utf8" => Box::new(
+            Utf8Array::<i32>::try_new(
+                ArrowDataType::Utf8,
+                typed_array_to_vec!(get(desc, "offsets")?, js_sys::Int32Array).into(),
+                typed_array_to_vec!(data, js_sys::Uint8Array).into(),
+                validity,
+            )
+            .map_err(JsPolarsErr::from)?,
+        ) as ArrayRef,
+        "LargeUtf8" => Box::new(
+            Utf8Array::<i64>::try_new(
+                ArrowDataType::LargeUtf8,
+                typed_array_to_vec!(get(desc, "offsets")?, js_sys::BigInt64Array).into(),
+                typed_array_to_vec!(data, js_sys::Uint8Array).into(),
+                validity,
+            )
+            .map_err(JsPolarsErr::from)?,
+        ) as ArrayRef,
+        "LargeList" => {
+            let values = children_from_js(desc)?
+                .into_iter()
+                .next()
+                .ok_or_else(|| JsPolarsErr::Other("a LargeList should have one child".into()))?;
+            let field = ArrowField::new("item", values.data_type().clone(), true);
+            Box::new(
+                ListArray::<i64>::try_new(
+                    ArrowDataType::LargeList(Box::new(field)),
+                    typed_array_to_vec!(get(desc, "offsets")?, js_sys::BigInt64Array).into(),
+                    values,
+                    validity,
+                )
+                .map_err(JsPolarsErr::from)?,
+            ) as ArrayRef
+        }
+        "Struct" => {
+            let names = get(desc, "fields")?;
+            if !js_sys::Array::is_array(&names) {
+                return Err(JsPolarsErr::Other("fields should be an array".into()).into());
+            }
+            let children = children_from_js(desc)?;
+            let fields = js_sys::Array::from(&names)
+                .iter()
+                .zip(&children)
+                .map(|(name, child)| {
+                    let name = name.as_string().ok_or_else(|| {
+                        JsPolarsErr::Other("field names should be strings".into())
+                    })?;
+                    Ok(ArrowField::new(name, child.data_type().clone(), true))
+                })
+                .collect::<JsResult<Vec<_>>>()?;
+            Box::new(
+                StructArray::try_new(ArrowDataType::Struct(fields), children, validity)
+                    .map_err(JsPolarsErr::from)?,
+            ) as ArrayRef
+        }
+        "Dictionary" => {
+            let keys: Vec<u32> = typed_array_to_vec!(data, js_sys::Uint32Array);
+            let keys = PrimitiveArray::<u32>::try_new(ArrowDataType::UInt32, keys.into(), validity)
+                .map_err(JsPolarsErr::from)?;
+            let values = array_from_js(&get(desc, "dictionary")?)?;
+            Box::new(
+                DictionaryArray::<u32>::try_from_keys(keys, values).map_err(JsPolarsErr::from)?,
+            ) as ArrayRef
+        }
+        ty => {
+            return Err(
+                JsPolarsErr::Other(format!("importing {} arrays is not supported", ty)).into(),
+            )
+        }
+    };
+    if arr.len() != length {
+        return Err(JsPolarsErr::Other(format!(
+            "{} array has {} values but its length is {}",
+            ty,
+            arr.len(),
+            length
+        ))
+        .into());
+    }
+    Ok(arr)
+}
+
+/// Build a Series out of a column description as returned by `ArrowBuffers.columns`.
+/// The buffers are copied into wasm memory.
+pub(crate) fn series_from_js(column: &JsValue) -> JsResult<Series> {
+    let name = get(column, "name")?
+        .as_string()
+        .ok_or_else(|| JsPolarsErr::Other("column name should be a string".into()))?;
+    let arr = array_from_js(column)?;
+    let s = Series::try_from((name.as_str(), arr)).map_err(JsPolarsErr::from)?;
+    Ok(s)
+}
@@ -1,9 +1,11 @@
+pub mod arrow;
 pub mod extern_iterator;
 pub mod extern_struct;
 pub mod from;
 pub mod prelude;
 
-use crate::JsResult;
+use crate::datatypes::TypedArrayType;
+use crate::{error::JsPolarsErr, JsResult};
 use polars::prelude::*;
 use wasm_bindgen::convert::FromWasmAbi;
 use wasm_bindgen::prelude::*;
@@ -97,7 +99,7 @@ impl FromJsValue for AnyValue<'_> {
             _ => {
                 if js_sys::Date::is_type_of(&jsv) {
                     let js_date = js_sys::Date::unchecked_from_js(jsv);
-                    let ms = js_date.get_milliseconds();
+                    let ms = js_date.get_time();
 
                     AnyValue::Datetime(ms as i64, TimeUnit::Milliseconds, &None)
                 } else if js_sys::Array::is_array(&jsv) {
@@ -112,28 +114,45 @@ impl FromJsValue for AnyValue<'_> {
 
 impl From<Wrap<&Series>> for JsValue {
     fn from(val: Wrap<&Series>) -> Self {
-        let s = val.0;
-        let len = s.len();
-        let dtype = s.dtype();
-
-        match dtype {
-            DataType::Struct(_) => {
-                let ca = s.struct_().unwrap();
-                let df: DataFrame = ca.clone().into();
-                df_to_struct(&df).unwrap().into()
-            }
-            _ => {
-                let arr = js_sys::Array::new_with_length(len as u32);
+        series_to_js(val.0).unwrap_throw()
+    }
+}
 
-                for (idx, val) in s.iter().enumerate() {
-                    arr.set(idx as u32, Wrap(val).into());
-                }
-                arr.into()
+/// The values of a Series as an array, or as an array of `{field: value}` objects for structs.
+pub(crate) fn series_to_js(s: &Series) -> JsResult<JsValue> {
+    match s.dtype() {
+        DataType::Struct(_) => {
+            let df: DataFrame = s.struct_().map_err(JsPolarsErr::from)?.clone().into();
+            Ok(df_to_struct(&df)?.into())
+        }
+        _ => {
+            let arr = js_sys::Array::new_with_length(s.len() as u32);
+            for (idx, val) in s.iter().enumerate() {
+                arr.set(idx as u32, Wrap(val).into());
             }
+            Ok(arr.into())
         }
     }
 }
 
+/// Milliseconds in `v` of `tu`, the unit of JS dates.
+fn to_milliseconds(v: i64, tu: TimeUnit) -> f64 {
+    match tu {
+        TimeUnit::Nanoseconds => v as f64 / 1_000_000.0,
+        TimeUnit::Microseconds => v as f64 / 1_000.0,
+        TimeUnit::Milliseconds => v as f64,
+    }
+}
+
+fn struct_to_js(values: &[AnyValue], fields: &[Field]) -> JsValue {
+    let obj = js_sys::Object::new();
+    for (av, field) in values.iter().zip(fields) {
+        let value: JsValue = Wrap(av.clone()).into();
+        js_sys::Reflect::set(&obj, &field.name().as_str().into(), &value).unwrap_throw();
+    }
+    obj.into()
+}
+
 impl From<Wrap<AnyValue<'_>>> for JsValue {
     fn from(av: Wrap<AnyValue<'_>>) -> Self {
         match av.0 {
@@ -150,25 +169,298 @@ impl From<Wrap<AnyValue<'_>>> for JsValue {
             AnyValue::Null => JsValue::null(),
             AnyValue::Boolean(v) => v.into(),
             AnyValue::Utf8(v) => v.into(),
+            AnyValue::Utf8Owned(v) => v.into(),
             AnyValue::Categorical(idx, rev) => {
                 let s = rev.get(idx);
                 s.into()
             }
             AnyValue::Date(v) => {
-                let jsv: JsValue = v.into();
+                // days since the epoch
+                let jsv: JsValue = (v as f64 * 86_400_000.0).into();
                 js_sys::Date::new(&jsv).into()
             }
-            AnyValue::Datetime(v, _, _) => {
-                let jsv: JsValue = v.into();
+            AnyValue::Datetime(v, tu, _) => {
+                let jsv: JsValue = to_milliseconds(v, tu).into();
                 let dt = js_sys::Date::new(&jsv);
                 dt.into()
             }
+            // JS has no duration type, durations are returned as a number of milliseconds
+            AnyValue::Duration(v, tu) => to_milliseconds(v, tu).into(),
             AnyValue::Time(v) => v.into(),
-            _ => todo!(),
+            AnyValue::List(s) => series_to_js(&s).unwrap_throw(),
+            AnyValue::Struct(values, fields) => struct_to_js(&values, fields),
+            AnyValue::StructOwned(payload) => struct_to_js(&payload.0, &payload.1),
+            av => av.to_string().into(),
         }
     }
 }
 
-pub fn df_to_struct(_df: &DataFrame) -> JsResult<js_sys::Array> {
-    todo!()
+/// Copy a numeric Series without nulls into the matching typed array.
+/// Returns `None` for other dtypes, or if the Series contains nulls.
+pub(crate) fn series_to_typed_array(s: &Series) -> Option<TypedArrayType> {
+    macro_rules! copy {
+        ($ca:expr, $variant:ident, $arr:ty) => {
+            $ca.cont_slice()
+                .ok()
+                .map(|values| TypedArrayType::$variant(<$arr>::from(values)))
+        };
+    }
+    if s.null_count() > 0 {
+        return None;
+    }
+    let s = s.rechunk();
+    match s.dtype() {
+        DataType::Int8 => copy!(s.i8().unwrap(), Int8, js_sys::Int8Array),
+        DataType::UInt8 => copy!(s.u8().unwrap(), Uint8, js_sys::Uint8Array),
+        DataType::Int16 => copy!(s.i16().unwrap(), Int16, js_sys::Int16Array),
+        DataType::UInt16 => copy!(s.u16().unwrap(), Uint16, js_sys::Uint16Array),
+        DataType::Int32 => copy!(s.i32().unwrap(), Int32, js_sys::Int32Array),
+        DataType::UInt32 => copy!(s.u32().unwrap(), Uint32, js_sys::Uint32Array),
+        DataType::Float32 => copy!(s.f32().unwrap(), Float32, js_sys::Float32Array),
+        DataType::Float64 => copy!(s.f64().unwrap(), Float64, js_sys::Float64Array),
+        DataType::Int64 => copy!(s.i64().unwrap(), BigInt64, js_sys::BigInt64Array),
+        DataType::UInt64 => copy!(s.u64().unwrap(), BigUint64, js_sys::BigUint64Array),
+        _ => None,
+    }
+}
+
+/// A value of a JS record, as read by `DataFrame.from_records`.
+pub(crate) enum RecordValue {
+    Null,
+    Boolean(bool),
+    Float64(f64),
+    Int64(i64),
+    UInt64(u64),
+    /// Milliseconds since the epoch of a JS `Date`.
+    Datetime(i64),
+    Utf8(String),
+}
+
+impl RecordValue {
+    /// Arrays and objects are kept as their JSON text.
+    pub(crate) fn from_js(jsv: JsValue) -> JsResult<Self> {
+        if jsv.is_null() || jsv.is_undefined() {
+            return Ok(RecordValue::Null);
+        }
+        if let Some(b) = jsv.as_bool() {
+            return Ok(RecordValue::Boolean(b));
+        }
+        if let Some(n) = jsv.as_f64() {
+            return Ok(RecordValue::Float64(n));
+        }
+        if let Some(s) = jsv.as_string() {
+            return Ok(RecordValue::Utf8(s));
+        }
+        if jsv.is_bigint() {
+            let text: String = js_sys::BigInt::unchecked_from_js(jsv).to_string(10)?.into();
+            // `UInt64` only for values beyond `i64`, so small values of either sign share a column
+            return match (text.parse::<i64>(), text.parse::<u64>()) {
+                (Ok(v), _) => Ok(RecordValue::Int64(v)),
+                (_, Ok(v)) => Ok(RecordValue::UInt64(v)),
+                _ => Err(
+                    JsPolarsErr::Other(format!("BigInt {} doesn't fit in 64 bits", text)).into(),
+                ),
+            };
+        }
+        if js_sys::Date::is_type_of(&jsv) {
+            let ms = js_sys::Date::unchecked_from_js(jsv).get_time();
+            return Ok(RecordValue::Datetime(ms as i64));
+        }
+        // functions and symbols have no JSON text and are dropped, as by `JSON.stringify`
+        match JsValue::from(js_sys::JSON::stringify(&jsv)?).as_string() {
+            Some(json) => Ok(RecordValue::Utf8(json)),
+            None => Ok(RecordValue::Null),
+        }
+    }
+
+    fn dtype(&self) -> Option<DataType> {
+        match self {
+            RecordValue::Null => None,
+            RecordValue::Boolean(_) => Some(DataType::Boolean),
+            RecordValue::Float64(_) => Some(DataType::Float64),
+            RecordValue::Int64(_) => Some(DataType::Int64),
+            RecordValue::UInt64(_) => Some(DataType::UInt64),
+            RecordValue::Datetime(_) => Some(DataType::Datetime(TimeUnit::Milliseconds, None)),
+            RecordValue::Utf8(_) => Some(DataType::Utf8),
+        }
+    }
+
+    /// Whether the value can be stored in a column of `dtype` as inferred by `infer_record_dtype`.
+    fn fits(&self, dtype: &DataType) -> bool {
+        match (self.dtype(), dtype) {
+            (None, _) | (_, DataType::Utf8) => true,
+            (Some(DataType::Int64 | DataType::UInt64), DataType::Float64) => true,
+            (Some(a), b) => &a == b,
+        }
+    }
+}
+
+/// The common dtype of two record values, numbers are upcast to `Float64`
+/// and any other mix falls back to `Utf8`.
+fn record_supertype(a: DataType, b: DataType) -> DataType {
+    use DataType::*;
+    match (a, b) {
+        (a, b) if a == b => a,
+        (Float64 | Int64 | UInt64, Float64 | Int64 | UInt64) => Float64,
+        _ => Utf8,
+    }
+}
+
+/// Infer the dtype of record values from the first `infer_schema_length` non null values.
+/// If a later value doesn't fit that dtype, the dtype is inferred from all values instead,
+/// so the column is upcast rather than the value lost.
+pub(crate) fn infer_record_dtype(
+    values: &[RecordValue],
+    infer_schema_length: Option<usize>,
+) -> DataType {
+    let infer = |n: usize| {
+        values
+            .iter()
+            .filter_map(|v| v.dtype())
+            .take(n)
+            .reduce(record_supertype)
+            .unwrap_or(DataType::Utf8)
+    };
+    let dtype = infer(infer_schema_length.unwrap_or(usize::MAX));
+    if values.iter().all(|v| v.fits(&dtype)) {
+        dtype
+    } else {
+        infer(usize::MAX)
+    }
+}
+
+/// Build a Series of `dtype`, as returned by `infer_record_dtype`, out of record values.
+pub(crate) fn records_to_series(name: &str, values: Vec<RecordValue>, dtype: &DataType) -> Series {
+    let values = values.into_iter();
+    match dtype {
+        DataType::Boolean => BooleanChunked::from_iter_options(
+            name,
+            values.map(|v| match v {
+                RecordValue::Boolean(b) => Some(b),
+                _ => None,
+            }),
+        )
+        .into_series(),
+        DataType::Float64 => Float64Chunked::from_iter_options(
+            name,
+            values.map(|v| match v {
+                RecordValue::Float64(v) => Some(v),
+                RecordValue::Int64(v) => Some(v as f64),
+                RecordValue::UInt64(v) => Some(v as f64),
+                _ => None,
+            }),
+        )
+        .into_series(),
+        DataType::Int64 => Int64Chunked::from_iter_options(
+            name,
+            values.map(|v| match v {
+                RecordValue::Int64(v) => Some(v),
+                _ => None,
+            }),
+        )
+        .into_series(),
+        DataType::UInt64 => UInt64Chunked::from_iter_options(
+            name,
+            values.map(|v| match v {
+                RecordValue::UInt64(v) => Some(v),
+                _ => None,
+            }),
+        )
+        .into_series(),
+        DataType::Datetime(tu, _) => Int64Chunked::from_iter_options(
+            name,
+            values.map(|v| match v {
+                RecordValue::Datetime(v) => Some(v),
+                _ => None,
+            }),
+        )
+        .into_datetime(*tu, None)
+        .into_series(),
+        _ => Utf8Chunked::from_iter_options(
+            name,
+            values.map(|v| match v {
+                RecordValue::Null => None,
+                RecordValue::Boolean(b) => Some(b.to_string()),
+                RecordValue::Float64(v) => Some(v.to_string()),
+                RecordValue::Int64(v) => Some(v.to_string()),
+                RecordValue::UInt64(v) => Some(v.to_string()),
+                RecordValue::Datetime(v) => {
+                    Some(AnyValue::Datetime(v, TimeUnit::Milliseconds, &None).to_string())
+                }
+                RecordValue::Utf8(s) => Some(s),
+            }),
+        )
+        .into_series(),
+    }
+}
+
+/// The rows of `df` as objects of `{column: value}`.
+pub fn df_to_struct(df: &DataFrame) -> JsResult<js_sys::Array> {
+    let rows = js_sys::Array::new_with_length(df.height() as u32);
+    for idx in 0..df.height() {
+        let obj = js_sys::Object::new();
+        for s in df.get_columns() {
+            let value: JsValue = Wrap(s.get(idx).map_err(JsPolarsErr::from)?).into();
+            js_sys::Reflect::set(&obj, &s.name().into(), &value)?;
+        }
+        rows.set(idx as u32, obj.into());
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_infer_record_dtype() {
+        use RecordValue::{Boolean, Float64, Int64, Null, Utf8};
+        assert_eq!(
+            infer_record_dtype(&[Int64(1), Null, Int64(-3)], None),
+            DataType::Int64
+        );
+        assert_eq!(
+            infer_record_dtype(&[Int64(1), Float64(2.5)], None),
+            DataType::Float64
+        );
+        assert_eq!(
+            infer_record_dtype(&[Boolean(true), Utf8("a".into())], None),
+            DataType::Utf8
+        );
+        assert_eq!(infer_record_dtype(&[Null, Null], None), DataType::Utf8);
+        // a value past `infer_schema_length` that doesn't fit upcasts the column
+        assert_eq!(
+            infer_record_dtype(&[Boolean(true), Boolean(false), Float64(1.0)], Some(1)),
+            DataType::Utf8
+        );
+        assert_eq!(
+            infer_record_dtype(&[Int64(1), Int64(2), Float64(1.5)], Some(1)),
+            DataType::Float64
+        );
+    }
+
+    #[test]
+    fn test_records_to_series() {
+        use RecordValue::{Boolean, Datetime, Float64, Int64, Null, Utf8};
+        let s = records_to_series("a", vec![Int64(1), Null, Float64(2.5)], &DataType::Float64);
+        assert!(s.series_equal_missing(&Series::new("a", [Some(1.0), None, Some(2.5)])));
+
+        let s = records_to_series(
+            "b",
+            vec![Boolean(true), Int64(3), Utf8("x".into()), Null],
+            &DataType::Utf8,
+        );
+        assert!(s.series_equal_missing(&Series::new(
+            "b",
+            [Some("true"), Some("3"), Some("x"), None]
+        )));
+
+        let s = records_to_series(
+            "c",
+            vec![Datetime(0), Null],
+            &DataType::Datetime(TimeUnit::Milliseconds, None),
+        );
+        assert_eq!(s.dtype(), &DataType::Datetime(TimeUnit::Milliseconds, None));
+        assert_eq!(s.null_count(), 1);
+    }
 }
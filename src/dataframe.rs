@@ -1,13 +1,20 @@
 use super::{error::JsPolarsErr, series::*, JsResult};
+use crate::conversion::arrow::{series_from_js, JsArrowBuffers};
 use crate::conversion::*;
-use crate::datatypes::JsDataType;
+use crate::datatypes::dtype_name;
+use crate::io::{
+    str_to_byte, str_to_ipc_compression, str_to_parquet_compression, str_to_quote_style,
+};
+use crate::utils::{js_option_bool, js_option_string, js_option_usize, js_to_column_names};
+use polars::lazy::physical_plan::state::ExecutionState;
+use polars::prelude::pivot::{pivot_stable, PivotAgg};
 use polars::prelude::*;
 
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(js_name=DataFrame)]
 pub struct JsDataFrame {
-    df: DataFrame,
+    pub(crate) df: DataFrame,
 }
 
 impl JsDataFrame {
@@ -21,6 +28,49 @@ impl From<DataFrame> for JsDataFrame {
     }
 }
 
+/// An expression used as the aggregation of `pivot`, evaluated on the values of each cell.
+struct PivotExpr {
+    expr: Expr,
+    /// `pivot` names the values after the column the expression refers to.
+    root: String,
+}
+
+impl PivotExpr {
+    fn try_new(expr: Expr) -> JsResult<Self> {
+        match expr_to_root_column_names(&expr).as_slice() {
+            [root] => Ok(PivotExpr {
+                root: root.to_string(),
+                expr,
+            }),
+            _ => Err(JsPolarsErr::Other(
+                "the aggregation of pivot should refer to exactly one column, such as pl.col(\"value\")"
+                    .into(),
+            )
+            .into()),
+        }
+    }
+}
+
+impl PhysicalAggExpr for PivotExpr {
+    fn evaluate(&self, df: &DataFrame, groups: &GroupsProxy) -> PolarsResult<Series> {
+        let state = ExecutionState::new();
+        let dtype = df
+            .get_columns()
+            .first()
+            .ok_or_else(|| PolarsError::NoData("pivot has no values to aggregate".into()))?
+            .dtype();
+        let phys_expr =
+            prepare_expression_for_context("", &self.expr, dtype, Context::Aggregation)?;
+        phys_expr
+            .evaluate_on_groups(df, groups, &state)
+            .map(|mut ac| ac.aggregated())
+    }
+
+    fn root_name(&self) -> PolarsResult<&str> {
+        Ok(&self.root)
+    }
+}
+
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(typescript_type = "DataFrame")]
@@ -33,6 +83,20 @@ extern "C" {
     fn ptr(this: &ExternDataFrame) -> f64;
     #[wasm_bindgen(typescript_type = "DataFrame[]")]
     pub type DataFrameArray;
+
+    #[wasm_bindgen(
+        typescript_type = "{ hasHeader?: boolean; sep?: string; quote?: string; \
+        quoteStyle?: \"necessary\" | \"always\" | \"non_numeric\"; nullValue?: string; \
+        dateFormat?: string; timeFormat?: string; datetimeFormat?: string; \
+        floatPrecision?: number; batchSize?: number }"
+    )]
+    pub type WriteCsvOptions;
+
+    #[wasm_bindgen(
+        typescript_type = "{ compression?: \"uncompressed\" | \"snappy\" | \"lz4\" | \"zstd\"; \
+        compressionLevel?: number; statistics?: boolean; rowGroupSize?: number }"
+    )]
+    pub type WriteParquetOptions;
 }
 
 #[wasm_bindgen(js_class=DataFrame)]
@@ -78,8 +142,8 @@ impl JsDataFrame {
             "cross" => JoinType::Cross,
             _ => panic!("not supported"),
         };
-        let left_on: Vec<String> = left_on.iter().map(|v| v.as_string().unwrap()).collect();
-        let right_on: Vec<String> = right_on.iter().map(|v| v.as_string().unwrap()).collect();
+        let left_on = js_to_column_names(&left_on, "left_on")?;
+        let right_on = js_to_column_names(&right_on, "right_on")?;
 
         let df = self
             .df
@@ -116,11 +180,7 @@ impl JsDataFrame {
         self.df
             .dtypes()
             .iter()
-            .map(|arrow_dtype| {
-                let dt: JsDataType = arrow_dtype.into();
-                let jsv: JsValue = dt.to_string().into();
-                jsv
-            })
+            .map(|dtype| JsValue::from(dtype_name(dtype)))
             .collect()
     }
     pub fn n_chunks(&self) -> JsResult<usize> {
@@ -191,7 +251,7 @@ impl JsDataFrame {
         Ok(series)
     }
     pub fn select(&self, selection: js_sys::Array) -> JsResult<JsDataFrame> {
-        let selection: Vec<String> = selection.iter().map(|v| v.as_string().unwrap()).collect();
+        let selection = js_to_column_names(&selection, "selection")?;
 
         let df = self.df.select(&selection).map_err(JsPolarsErr::from)?;
         Ok(JsDataFrame::new(df))
@@ -302,13 +362,82 @@ impl JsDataFrame {
         let df = self
             .df
             .melt(
-                id_vars.iter().map(|v| v.as_string().unwrap()),
-                value_vars.iter().map(|v| v.as_string().unwrap()),
+                js_to_column_names(&id_vars, "id_vars")?,
+                js_to_column_names(&value_vars, "value_vars")?,
             )
             .map_err(JsPolarsErr::from)?;
         Ok(JsDataFrame::new(df))
     }
 
+    /// Create a spreadsheet-style pivot table, the inverse of `melt`.
+    /// Rows keep the order in which their index values first appear.
+    /// @param values - columns whose values fill the table
+    /// @param index - columns that identify the rows
+    /// @param columns - columns whose values become the new column names
+    /// @param aggregate_fn - one of `first`, `last`, `sum`, `min`, `max`, `mean`, `median` or `count`,
+    /// or an expression aggregating the values of each cell, such as `pl.col("value").std()`
+    /// @param sort_columns - sort the new columns by name
+    /// @example
+    /// ```js
+    /// > const long = df.melt(["date"], ["a", "b"])
+    /// > long.pivot(["value"], ["date"], ["variable"], "first", false)
+    /// > long.pivot(["value"], ["date"], ["variable"], pl.col("value").std(), false)
+    /// ```
+    pub fn pivot(
+        &self,
+        values: js_sys::Array,
+        index: js_sys::Array,
+        columns: js_sys::Array,
+        aggregate_fn: JsValue,
+        sort_columns: bool,
+    ) -> JsResult<JsDataFrame> {
+        let agg_fn = match aggregate_fn.as_string().as_deref() {
+            Some("first") => PivotAgg::First,
+            Some("last") => PivotAgg::Last,
+            Some("sum") => PivotAgg::Sum,
+            Some("min") => PivotAgg::Min,
+            Some("max") => PivotAgg::Max,
+            Some("mean") => PivotAgg::Mean,
+            Some("median") => PivotAgg::Median,
+            Some("count") => PivotAgg::Count,
+            Some(s) => {
+                return Err(JsPolarsErr::Other(format!(
+                    "aggregate_fn should be one of first, last, sum, min, max, mean, median, count or an expression, got {}",
+                    s
+                ))
+                .into())
+            }
+            None => PivotAgg::Expr(Arc::new(PivotExpr::try_new(
+                crate::lazy::dataframe::js_expr_to_expr(&aggregate_fn)?,
+            )?)),
+        };
+        let df = pivot_stable(
+            &self.df,
+            js_to_column_names(&values, "values")?,
+            js_to_column_names(&index, "index")?,
+            js_to_column_names(&columns, "columns")?,
+            agg_fn,
+            sort_columns,
+        )
+        .map_err(JsPolarsErr::from)?;
+        Ok(JsDataFrame::new(df))
+    }
+
+    /// Start a groupby operation.
+    /// @param by - column names or expressions to group by
+    /// @param maintain_order - keep the groups in the order they first appear, this is slower
+    /// @example
+    /// ```js
+    /// > df.groupby(["group"], true).agg([pl.col("value").sum()])
+    /// ```
+    pub fn groupby(
+        &self,
+        by: &js_sys::Array,
+        maintain_order: bool,
+    ) -> JsResult<crate::groupby::JsGroupBy> {
+        crate::groupby::JsGroupBy::new(self.df.clone(), by, maintain_order)
+    }
+
     pub fn shift(&self, periods: f64) -> Self {
         self.df.shift(periods as i64).into()
     }
@@ -323,10 +452,9 @@ impl JsDataFrame {
             "last" => UniqueKeepStrategy::Last,
             s => panic!("keep strategy {} is not supported", s),
         };
-        let subset: Option<Vec<String>> = subset.map(|v| {
-            let s: Vec<String> = v.iter().map(|item| item.as_string().unwrap()).collect();
-            s
-        });
+        let subset = subset
+            .map(|v| js_to_column_names(&v, "subset"))
+            .transpose()?;
 
         let subset = subset.as_ref().map(|v| v.as_ref());
 
@@ -371,6 +499,271 @@ impl JsDataFrame {
         df.into()
     }
 
+    /// Serialize the DataFrame to CSV bytes.
+    /// @param options - `quoteStyle` is one of `necessary`, `always` or `non_numeric`,
+    /// defaults to `{hasHeader: true, sep: ",", quote: '"', quoteStyle: "necessary",
+    /// nullValue: "", batchSize: 1024}`
+    pub fn write_csv(&mut self, options: Option<WriteCsvOptions>) -> JsResult<Vec<u8>> {
+        let options: JsValue = options.map_or(JsValue::UNDEFINED, Into::into);
+        let sep = js_option_string(&options, "sep")?;
+        let quote = js_option_string(&options, "quote")?;
+        let quote_style = js_option_string(&options, "quoteStyle")?;
+        let mut buf: Vec<u8> = Vec::new();
+        CsvWriter::new(&mut buf)
+            .has_header(js_option_bool(&options, "hasHeader")?.unwrap_or(true))
+            .with_delimiter(str_to_byte(sep.as_deref().unwrap_or(","), "sep")?)
+            .with_quoting_char(str_to_byte(quote.as_deref().unwrap_or("\""), "quote")?)
+            .with_quote_style(str_to_quote_style(
+                quote_style.as_deref().unwrap_or("necessary"),
+            )?)
+            .with_null_value(js_option_string(&options, "nullValue")?.unwrap_or_default())
+            .with_date_format(js_option_string(&options, "dateFormat")?)
+            .with_time_format(js_option_string(&options, "timeFormat")?)
+            .with_datetime_format(js_option_string(&options, "datetimeFormat")?)
+            .with_float_precision(js_option_usize(&options, "floatPrecision")?)
+            .with_batch_size(js_option_usize(&options, "batchSize")?.unwrap_or(1024))
+            .finish(&mut self.df)
+            .map_err(JsPolarsErr::from)?;
+        Ok(buf)
+    }
+
+    /// Serialize the DataFrame to Parquet bytes.
+    /// @param options - `compression` is one of `uncompressed`, `snappy`, `lz4` or `zstd`,
+    /// `compressionLevel` is only used by `zstd`.
+    /// Defaults to `{compression: "zstd", statistics: false}`
+    pub fn write_parquet(&mut self, options: Option<WriteParquetOptions>) -> JsResult<Vec<u8>> {
+        let options: JsValue = options.map_or(JsValue::UNDEFINED, Into::into);
+        let compression = js_option_string(&options, "compression")?;
+        let compression_level = js_option_usize(&options, "compressionLevel")?;
+        let compression = str_to_parquet_compression(
+            compression.as_deref().unwrap_or("zstd"),
+            compression_level.map(|level| level as i32),
+        )?;
+        let mut buf: Vec<u8> = Vec::new();
+        ParquetWriter::new(&mut buf)
+            .with_compression(compression)
+            .with_statistics(js_option_bool(&options, "statistics")?.unwrap_or(false))
+            .with_row_group_size(js_option_usize(&options, "rowGroupSize")?)
+            .finish(&mut self.df)
+            .map_err(JsPolarsErr::from)?;
+        Ok(buf)
+    }
+
+    /// Serialize the DataFrame to Arrow IPC (Feather v2) bytes.
+    /// @param compression - one of `uncompressed`, `lz4` or `zstd`
+    pub fn write_ipc(&mut self, compression: &str) -> JsResult<Vec<u8>> {
+        let compression = str_to_ipc_compression(compression)?;
+        let mut buf: Vec<u8> = Vec::new();
+        IpcWriter::new(&mut buf)
+            .with_compression(compression)
+            .finish(&mut self.df)
+            .map_err(JsPolarsErr::from)?;
+        Ok(buf)
+    }
+
+    /// Move the DataFrame out of wasm memory as an Arrow IPC buffer.
+    /// The DataFrame is consumed and its memory freed; the returned buffer is owned by JS
+    /// and can be transferred to another worker or wasm instance and restored with `transfer_in`.
+    pub fn transfer_out(self) -> JsResult<Vec<u8>> {
+        let mut df = self.df;
+        let mut buf: Vec<u8> = Vec::new();
+        IpcWriter::new(&mut buf)
+            .finish(&mut df)
+            .map_err(JsPolarsErr::from)?;
+        Ok(buf)
+    }
+
+    /// Restore a DataFrame from a buffer produced by `transfer_out`.
+    pub fn transfer_in(buf: &[u8]) -> JsResult<JsDataFrame> {
+        let df = IpcReader::new(std::io::Cursor::new(buf))
+            .finish()
+            .map_err(JsPolarsErr::from)?;
+        Ok(df.into())
+    }
+
+    /// Serialize the DataFrame to JSON.
+    /// @param orient - `row` writes an array of row objects,
+    /// `column` writes the columns with their names and dtypes
+    pub fn write_json(&mut self, orient: &str) -> JsResult<Vec<u8>> {
+        let mut buf: Vec<u8> = Vec::new();
+        match orient {
+            "row" => JsonWriter::new(&mut buf)
+                .with_json_format(JsonFormat::Json)
+                .finish(&mut self.df)
+                .map_err(JsPolarsErr::from)?,
+            "column" => serde_json::to_writer(&mut buf, &self.df)
+                .map_err(|e| JsPolarsErr::Other(format!("{}", e)))?,
+            s => {
+                return Err(JsPolarsErr::Other(format!(
+                    "orient should be one of row, column, got {}",
+                    s
+                ))
+                .into())
+            }
+        };
+        Ok(buf)
+    }
+
+    /// Serialize the DataFrame to newline delimited JSON, one object per row.
+    pub fn write_ndjson(&mut self) -> JsResult<Vec<u8>> {
+        let mut buf: Vec<u8> = Vec::new();
+        JsonWriter::new(&mut buf)
+            .with_json_format(JsonFormat::JsonLines)
+            .finish(&mut self.df)
+            .map_err(JsPolarsErr::from)?;
+        Ok(buf)
+    }
+
+    /// Serialize the DataFrame to an Avro container file.
+    /// @param compression - one of `uncompressed`, `deflate` or `snappy`
+    pub fn write_avro(&mut self, compression: &str) -> JsResult<Vec<u8>> {
+        let compression = match compression {
+            "uncompressed" => None,
+            "deflate" => Some(AvroCompression::Deflate),
+            "snappy" => Some(AvroCompression::Snappy),
+            s => {
+                return Err(JsPolarsErr::Other(format!(
+                    "compression should be one of uncompressed, deflate, snappy, got {}",
+                    s
+                ))
+                .into())
+            }
+        };
+        let mut buf: Vec<u8> = Vec::new();
+        AvroWriter::new(&mut buf)
+            .with_compression(compression)
+            .finish(&mut self.df)
+            .map_err(JsPolarsErr::from)?;
+        Ok(buf)
+    }
+
+    /// Serialize the DataFrame with its schema, for persistence.
+    /// @param format - `bincode` for a compact binary encoding, or `json`
+    pub fn serialize(&self, format: &str) -> JsResult<Vec<u8>> {
+        let buf =
+            match format {
+                "bincode" => bincode::serialize(&self.df)
+                    .map_err(|e| JsPolarsErr::Other(format!("{}", e)))?,
+                "json" => serde_json::to_vec(&self.df)
+                    .map_err(|e| JsPolarsErr::Other(format!("{}", e)))?,
+                s => {
+                    return Err(JsPolarsErr::Other(format!(
+                        "format should be one of bincode, json, got {}",
+                        s
+                    ))
+                    .into())
+                }
+            };
+        Ok(buf)
+    }
+
+    /// Restore a DataFrame written by `serialize`.
+    /// @param format - the format passed to `serialize`
+    pub fn deserialize(buf: &[u8], format: &str) -> JsResult<JsDataFrame> {
+        let df: DataFrame = match format {
+            "bincode" => {
+                bincode::deserialize(buf).map_err(|e| JsPolarsErr::Other(format!("{}", e)))?
+            }
+            "json" => {
+                serde_json::from_slice(buf).map_err(|e| JsPolarsErr::Other(format!("{}", e)))?
+            }
+            s => {
+                return Err(JsPolarsErr::Other(format!(
+                    "format should be one of bincode, json, got {}",
+                    s
+                ))
+                .into())
+            }
+        };
+        Ok(df.into())
+    }
+
+    /// Expose the Arrow buffers of every column as typed array views into wasm memory.
+    /// This avoids converting every value to a JS value, e.g. to build an Apache Arrow JS `Table`.
+    pub fn to_arrow_buffers(&self) -> JsArrowBuffers {
+        let mut df = self.df.clone();
+        df.rechunk();
+        JsArrowBuffers::new(&df)
+    }
+
+    /// Build a DataFrame from column descriptions as returned by `ArrowBuffers.columns`.
+    pub fn from_arrow_buffers(columns: js_sys::Array) -> JsResult<JsDataFrame> {
+        let columns = columns
+            .iter()
+            .map(|column| series_from_js(&column))
+            .collect::<JsResult<Vec<_>>>()?;
+        let df = DataFrame::new(columns).map_err(JsPolarsErr::from)?;
+        Ok(df.into())
+    }
+
+    /// Build a DataFrame from an array of row objects.
+    /// Keys missing from some rows become nulls, nested arrays and objects are kept as JSON text.
+    /// A column whose later values don't fit the inferred dtype is upcast to a dtype that fits all of them.
+    /// @param schema - an object of `{column: dtype}` overriding the inferred dtypes
+    /// @param infer_schema_length - number of non null values used to infer the dtype of a column
+    pub fn from_records(
+        rows: js_sys::Array,
+        schema: JsValue,
+        infer_schema_length: Option<u32>,
+    ) -> JsResult<JsDataFrame> {
+        use wasm_bindgen::JsCast;
+
+        let schema = crate::io::js_to_schema(schema)?;
+        let infer_schema_length = infer_schema_length.map(|i| i as usize);
+        let height = rows.length() as usize;
+
+        let mut names: Vec<String> = vec![];
+        let mut columns: Vec<Vec<RecordValue>> = vec![];
+        let mut index: std::collections::HashMap<String, usize> = Default::default();
+        for (row_idx, row) in rows.iter().enumerate() {
+            if !row.is_object() {
+                return Err(
+                    JsPolarsErr::Other(format!("row {} should be an object", row_idx)).into(),
+                );
+            }
+            let row: js_sys::Object = row.unchecked_into();
+            for key in js_sys::Object::keys(&row).iter() {
+                let value = js_sys::Reflect::get(&row, &key)?;
+                let key = key.as_string().unwrap();
+                let col_idx = *index.entry(key.clone()).or_insert_with(|| {
+                    names.push(key);
+                    let mut values = Vec::with_capacity(height);
+                    values.resize_with(row_idx, || RecordValue::Null);
+                    columns.push(values);
+                    columns.len() - 1
+                });
+                columns[col_idx].push(RecordValue::from_js(value)?);
+            }
+            for values in columns.iter_mut() {
+                values.resize_with(row_idx + 1, || RecordValue::Null);
+            }
+        }
+
+        let mut columns = names
+            .iter()
+            .zip(columns)
+            .map(|(name, values)| {
+                let inferred = infer_record_dtype(&values, infer_schema_length);
+                let s = records_to_series(name, values, &inferred);
+                match schema.as_ref().and_then(|schema| schema.get(name)) {
+                    Some(dtype) if dtype != &inferred => s.cast(dtype),
+                    _ => Ok(s),
+                }
+            })
+            .collect::<PolarsResult<Vec<_>>>()
+            .map_err(JsPolarsErr::from)?;
+        // columns of the schema that no row has are all null
+        if let Some(schema) = &schema {
+            for (name, dtype) in schema.iter() {
+                if !index.contains_key(name.as_str()) {
+                    columns.push(Series::full_null(name, height, dtype));
+                }
+            }
+        }
+        let df = DataFrame::new(columns).map_err(JsPolarsErr::from)?;
+        Ok(df.into())
+    }
+
     pub fn to_records(&self) -> JsResult<js_sys::Array> {
         let height = self.df.height() as u32;
         let rows = js_sys::Array::new_with_length(height);
@@ -407,17 +800,22 @@ impl JsDataFrame {
         Ok(())
     }
 
-    pub fn to_object(&mut self) -> JsResult<js_sys::Object> {
-        todo!()
-        // let obj = js_sys::Object::new();
-        // self.df.rechunk();
-
-        // for col in self.df.get_columns() {
-        //     let key: JsValue = col.name().into();
-        //     let val: JsValue = Wrap(col).into();
-        //     js_sys::Reflect::set(&obj, &key, &val)?;
-        // }
+    /// Convert the DataFrame to an object of `{column: values}`.
+    /// @param typed_arrays - return numeric columns without nulls as the matching typed array
+    /// (`Float64Array`, `Int32Array`, `BigInt64Array`, ...) instead of an array
+    pub fn to_object(&mut self, typed_arrays: bool) -> JsResult<js_sys::Object> {
+        let obj = js_sys::Object::new();
+        self.df.rechunk();
+
+        for col in self.df.get_columns() {
+            let key: JsValue = col.name().into();
+            let val: JsValue = match typed_arrays.then(|| series_to_typed_array(col)).flatten() {
+                Some(arr) => arr.into(),
+                None => series_to_js(col)?,
+            };
+            js_sys::Reflect::set(&obj, &key, &val)?;
+        }
 
-        // Ok(obj)
+        Ok(obj)
     }
 }
@@ -20,6 +20,8 @@ pub enum JsDataType {
     Time,
     // Object,
     Categorical,
+    Duration,
+    Struct,
 }
 
 impl JsDataType {
@@ -43,15 +45,18 @@ impl JsDataType {
             JsDataType::Time => "Time",
             // JsDataType::Object => "Object",
             JsDataType::Categorical => "Categorical",
+            JsDataType::Duration => "Duration",
+            JsDataType::Struct => "Struct",
         }
         .to_owned()
     }
 }
 
-impl From<&DataType> for JsDataType {
-    fn from(dt: &DataType) -> Self {
+impl JsDataType {
+    /// `None` for the dtypes JS has no name for, e.g. `Null`.
+    pub(crate) fn try_from_dtype(dt: &DataType) -> Option<Self> {
         use JsDataType::*;
-        match dt {
+        let dt = match dt {
             DataType::Int8 => Int8,
             DataType::Int16 => Int16,
             DataType::Int32 => Int32,
@@ -71,10 +76,25 @@ impl From<&DataType> for JsDataType {
 
             // DataType::Object(_) => Object,
             DataType::Categorical(_) => Categorical,
-            _ => {
-                panic!("null or unknown not expected here")
-            }
-        }
+            DataType::Duration(_) => Duration,
+            DataType::Struct(_) => Struct,
+            _ => return None,
+        };
+        Some(dt)
+    }
+}
+
+impl From<&DataType> for JsDataType {
+    fn from(dt: &DataType) -> Self {
+        JsDataType::try_from_dtype(dt).expect("null or unknown not expected here")
+    }
+}
+
+/// The name of `dt` reported to JS, the polars name for dtypes without a `JsDataType`.
+pub(crate) fn dtype_name(dt: &DataType) -> String {
+    match JsDataType::try_from_dtype(dt) {
+        Some(js_dt) => js_dt.to_string(),
+        None => dt.to_string(),
     }
 }
 
@@ -123,3 +143,21 @@ impl From<wasm_bindgen::JsValue> for TypedArrayType {
         }
     }
 }
+
+impl From<TypedArrayType> for wasm_bindgen::JsValue {
+    fn from(v: TypedArrayType) -> Self {
+        match v {
+            TypedArrayType::Int8(v) => v.into(),
+            TypedArrayType::Uint8(v) => v.into(),
+            TypedArrayType::Uint8Clamped(v) => v.into(),
+            TypedArrayType::Int16(v) => v.into(),
+            TypedArrayType::Uint16(v) => v.into(),
+            TypedArrayType::Int32(v) => v.into(),
+            TypedArrayType::Uint32(v) => v.into(),
+            TypedArrayType::Float32(v) => v.into(),
+            TypedArrayType::Float64(v) => v.into(),
+            TypedArrayType::BigInt64(v) => v.into(),
+            TypedArrayType::BigUint64(v) => v.into(),
+        }
+    }
+}
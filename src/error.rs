@@ -1,3 +1,4 @@
+use polars::export::arrow::error::Error as ArrowError;
 use polars::prelude::PolarsError;
 use thiserror::Error;
 use wasm_bindgen::prelude::JsValue;
@@ -7,6 +8,8 @@ pub enum JsPolarsErr {
     #[error(transparent)]
     Any(#[from] PolarsError),
     #[error(transparent)]
+    Arrow(#[from] ArrowError),
+    #[error(transparent)]
     Serde(#[from] serde_wasm_bindgen::Error),
     #[error("{0}")]
     Other(String),
@@ -0,0 +1,141 @@
+use polars::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    conversion::Wrap,
+    dataframe::JsDataFrame,
+    error::JsPolarsErr,
+    lazy::dataframe::{js_expr_to_expr, js_exprs_to_exprs},
+    JsResult,
+};
+
+/// A DataFrame grouped by key columns or expressions, created with `DataFrame.groupby`.
+/// The aggregations are run through the lazy engine, so any `Expr` can be used in `agg`.
+#[wasm_bindgen(js_name = GroupBy)]
+pub struct JsGroupBy {
+    df: DataFrame,
+    by: Vec<Expr>,
+    maintain_order: bool,
+}
+
+impl JsGroupBy {
+    /// @param by - column names or expressions
+    pub(crate) fn new(df: DataFrame, by: &js_sys::Array, maintain_order: bool) -> JsResult<Self> {
+        let by = by
+            .iter()
+            .map(|key| match key.as_string() {
+                Some(name) => Ok(col(&name)),
+                None => js_expr_to_expr(&key),
+            })
+            .collect::<JsResult<Vec<_>>>()?;
+        Ok(JsGroupBy {
+            df,
+            by,
+            maintain_order,
+        })
+    }
+
+    fn key_names(&self) -> JsResult<Vec<String>> {
+        let schema = self
+            .df
+            .clone()
+            .lazy()
+            .select(&self.by)
+            .schema()
+            .map_err(JsPolarsErr::from)?;
+        Ok(schema.iter_names().cloned().collect())
+    }
+
+    /// All columns that are not a key.
+    fn values(&self) -> JsResult<Expr> {
+        let keys = self.key_names()?;
+        let values: Vec<String> = self
+            .df
+            .get_column_names()
+            .into_iter()
+            .filter(|name| !keys.iter().any(|key| key == name))
+            .map(|name| name.to_string())
+            .collect();
+        Ok(cols(values))
+    }
+
+    fn finish<E: AsRef<[Expr]>>(&self, aggs: E) -> JsResult<JsDataFrame> {
+        let ldf = self.df.clone().lazy();
+        let gb = match self.maintain_order {
+            true => ldf.groupby_stable(&self.by),
+            false => ldf.groupby(&self.by),
+        };
+        let df = gb.agg(aggs).collect().map_err(JsPolarsErr::from)?;
+        Ok(df.into())
+    }
+}
+
+#[wasm_bindgen(js_class = GroupBy)]
+impl JsGroupBy {
+    /// Compute aggregations for every group.
+    /// @param exprs - the aggregations, evaluated in the context of each group
+    /// @example
+    /// ```js
+    /// > df.groupby(["group"], true).agg([pl.col("value").sum(), pl.col("value").agg_groups().alias("rows")])
+    /// ```
+    pub fn agg(&self, exprs: &js_sys::Array) -> JsResult<JsDataFrame> {
+        self.finish(js_exprs_to_exprs(exprs)?)
+    }
+
+    /// The first value of every column in each group.
+    pub fn first(&self) -> JsResult<JsDataFrame> {
+        self.finish([self.values()?.first()])
+    }
+
+    /// The last value of every column in each group.
+    pub fn last(&self) -> JsResult<JsDataFrame> {
+        self.finish([self.values()?.last()])
+    }
+
+    /// The sum of every column in each group.
+    pub fn sum(&self) -> JsResult<JsDataFrame> {
+        self.finish([self.values()?.sum()])
+    }
+
+    /// The mean of every column in each group.
+    pub fn mean(&self) -> JsResult<JsDataFrame> {
+        self.finish([self.values()?.mean()])
+    }
+
+    /// The number of rows in each group, as a `count` column.
+    pub fn count(&self) -> JsResult<JsDataFrame> {
+        self.finish([count()])
+    }
+
+    /// The number of unique values of every column in each group.
+    pub fn n_unique(&self) -> JsResult<JsDataFrame> {
+        self.finish([self.values()?.n_unique()])
+    }
+
+    /// The quantile of every column in each group.
+    /// @param interpolation - one of `nearest`, `lower`, `higher`, `midpoint` or `linear`
+    pub fn quantile(
+        &self,
+        quantile: f64,
+        interpolation: Wrap<QuantileInterpolOptions>,
+    ) -> JsResult<JsDataFrame> {
+        self.finish([self.values()?.quantile(quantile, interpolation.0)])
+    }
+
+    /// The keys of every group with the row indexes of the group in a `groups` column.
+    pub fn groups(&self) -> JsResult<JsDataFrame> {
+        let keys = self
+            .df
+            .clone()
+            .lazy()
+            .select(&self.by)
+            .collect()
+            .map_err(JsPolarsErr::from)?;
+        let df = self
+            .df
+            .groupby_with_series(keys.get_columns().to_vec(), true, self.maintain_order)
+            .and_then(|gb| gb.groups())
+            .map_err(JsPolarsErr::from)?;
+        Ok(df.into())
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{error::JsPolarsErr, JsResult};
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use std::borrow::Cow;
+use std::io::{Read, Write};
+
+const GZIP: [u8; 2] = [0x1f, 0x8b];
+const ZSTD: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+// zlib headers of the default, best and fastest compression levels
+const ZLIB: [[u8; 2]; 3] = [[0x78, 0x9c], [0x78, 0xda], [0x78, 0x01]];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Compression {
+    Gzip,
+    Zlib,
+    Deflate,
+    Zstd,
+}
+
+impl Compression {
+    /// Detect the compression of `buf` by its magic bytes.
+    /// Raw deflate streams have no header and are never detected.
+    pub(crate) fn infer(buf: &[u8]) -> Option<Compression> {
+        if buf.starts_with(&GZIP) {
+            Some(Compression::Gzip)
+        } else if buf.starts_with(&ZSTD) {
+            Some(Compression::Zstd)
+        } else if ZLIB.iter().any(|magic| buf.starts_with(magic)) {
+            Some(Compression::Zlib)
+        } else {
+            None
+        }
+    }
+
+    /// Parse the `compression` option of the readers.
+    /// @param compression - one of `infer`, `uncompressed`, `gzip`, `zlib`, `deflate` or `zstd`
+    pub(crate) fn from_option(compression: &str, buf: &[u8]) -> JsResult<Option<Compression>> {
+        match compression {
+            "infer" => Ok(Compression::infer(buf)),
+            "uncompressed" => Ok(None),
+            "gzip" => Ok(Some(Compression::Gzip)),
+            "zlib" => Ok(Some(Compression::Zlib)),
+            "deflate" => Ok(Some(Compression::Deflate)),
+            "zstd" => Ok(Some(Compression::Zstd)),
+            s => Err(JsPolarsErr::Other(format!(
+                "compression should be one of infer, uncompressed, gzip, zlib, deflate, zstd, got {}",
+                s
+            ))
+            .into()),
+        }
+    }
+}
+
+fn read_all<R: Read>(mut reader: R, capacity: usize) -> JsResult<Vec<u8>> {
+    let mut out = Vec::with_capacity(capacity);
+    reader
+        .read_to_end(&mut out)
+        .map_err(|e| JsPolarsErr::Other(format!("failed to decompress input: {}", e)))?;
+    Ok(out)
+}
+
+/// The size of the decompressed data of a gzip stream, as stored in its last four bytes.
+/// Deflate can't compress more than about 1032:1, larger sizes come from a corrupt footer.
+fn gzip_size_hint(buf: &[u8]) -> usize {
+    match buf.len().checked_sub(4).map(|start| &buf[start..]) {
+        Some(&[a, b, c, d]) => (u32::from_le_bytes([a, b, c, d]) as usize).min(buf.len() * 1032),
+        _ => 0,
+    }
+}
+
+fn zstd_decompress(buf: &[u8]) -> JsResult<Vec<u8>> {
+    let mut source = buf;
+    let decoder = ruzstd::StreamingDecoder::new(&mut source)
+        .map_err(|e| JsPolarsErr::Other(format!("failed to decompress input: {}", e)))?;
+    read_all(decoder, buf.len())
+}
+
+/// Decompress `buf` inside wasm if it is compressed, otherwise borrow it as is.
+/// Only gzip records its decompressed size, the output of the other formats grows as needed.
+pub(crate) fn decompress<'a>(buf: &'a [u8], compression: &str) -> JsResult<Cow<'a, [u8]>> {
+    let out = match Compression::from_option(compression, buf)? {
+        None => return Ok(Cow::Borrowed(buf)),
+        Some(Compression::Gzip) => read_all(GzDecoder::new(buf), gzip_size_hint(buf))?,
+        Some(Compression::Zlib) => read_all(ZlibDecoder::new(buf), buf.len())?,
+        Some(Compression::Deflate) => read_all(DeflateDecoder::new(buf), buf.len())?,
+        Some(Compression::Zstd) => zstd_decompress(buf)?,
+    };
+    Ok(Cow::Owned(out))
+}
+
+/// Decompresses input that arrives in chunks.
+/// Only the flate based formats can be decoded incrementally, zstd input is
+/// collected and decoded as a whole by `finish`, as the decoder has to pull its input.
+pub(crate) enum ChunkedDecoder {
+    Uncompressed,
+    Gzip(flate2::write::GzDecoder<Vec<u8>>),
+    Zlib(flate2::write::ZlibDecoder<Vec<u8>>),
+    Deflate(flate2::write::DeflateDecoder<Vec<u8>>),
+    Zstd(Vec<u8>),
+}
+
+impl ChunkedDecoder {
+    pub(crate) fn new(compression: Option<Compression>) -> JsResult<ChunkedDecoder> {
+        match compression {
+            None => Ok(ChunkedDecoder::Uncompressed),
+            Some(Compression::Gzip) => Ok(ChunkedDecoder::Gzip(flate2::write::GzDecoder::new(
+                Vec::new(),
+            ))),
+            Some(Compression::Zlib) => Ok(ChunkedDecoder::Zlib(flate2::write::ZlibDecoder::new(
+                Vec::new(),
+            ))),
+            Some(Compression::Deflate) => Ok(ChunkedDecoder::Deflate(
+                flate2::write::DeflateDecoder::new(Vec::new()),
+            )),
+            Some(Compression::Zstd) => Ok(ChunkedDecoder::Zstd(Vec::new())),
+        }
+    }
+
+    /// Decode `chunk` and append the decompressed bytes to `out`.
+    pub(crate) fn decode(&mut self, chunk: &[u8], out: &mut Vec<u8>) -> JsResult<()> {
+        let res = match self {
+            ChunkedDecoder::Uncompressed => {
+                out.extend_from_slice(chunk);
+                return Ok(());
+            }
+            ChunkedDecoder::Gzip(d) => d.write_all(chunk).map(|_| out.append(d.get_mut())),
+            ChunkedDecoder::Zlib(d) => d.write_all(chunk).map(|_| out.append(d.get_mut())),
+            ChunkedDecoder::Deflate(d) => d.write_all(chunk).map(|_| out.append(d.get_mut())),
+            ChunkedDecoder::Zstd(input) => {
+                input.extend_from_slice(chunk);
+                return Ok(());
+            }
+        };
+        res.map_err(|e| JsPolarsErr::Other(format!("failed to decompress input: {}", e)).into())
+    }
+
+    /// Flush the last decompressed bytes into `out`.
+    pub(crate) fn finish(&mut self, out: &mut Vec<u8>) -> JsResult<()> {
+        let res = match self {
+            ChunkedDecoder::Uncompressed => return Ok(()),
+            ChunkedDecoder::Gzip(d) => d.try_finish().map(|_| out.append(d.get_mut())),
+            ChunkedDecoder::Zlib(d) => d.try_finish().map(|_| out.append(d.get_mut())),
+            ChunkedDecoder::Deflate(d) => d.try_finish().map(|_| out.append(d.get_mut())),
+            ChunkedDecoder::Zstd(input) => {
+                out.append(&mut zstd_decompress(input)?);
+                input.clear();
+                return Ok(());
+            }
+        };
+        res.map_err(|e| JsPolarsErr::Other(format!("failed to decompress input: {}", e)).into())
+    }
+}
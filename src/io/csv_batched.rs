@@ -0,0 +1,293 @@
+use crate::{dataframe::JsDataFrame, error::JsPolarsErr, JsResult};
+use polars::prelude::*;
+use std::io::Cursor;
+
+use wasm_bindgen::prelude::*;
+
+use super::compression::{ChunkedDecoder, Compression};
+use super::encoding::{ChunkedTextDecoder, TextEncoding};
+use super::{schema_to_js, str_to_byte};
+
+/// Position of the last line terminator in `buf` that is not inside a quoted field.
+fn last_line_end(buf: &[u8], quote_char: Option<u8>, eol_char: u8) -> Option<usize> {
+    let mut in_quotes = false;
+    let mut last = None;
+    for (i, &b) in buf.iter().enumerate() {
+        if Some(b) == quote_char {
+            in_quotes = !in_quotes;
+        } else if b == eol_char && !in_quotes {
+            last = Some(i);
+        }
+    }
+    last
+}
+
+/// Read a CSV file chunk by chunk, e.g. from `File.stream()`.
+///
+/// Incomplete lines are carried over to the next chunk. The schema is inferred
+/// from the first chunk that holds data rows and is used for all following batches.
+/// Batches are parsed on the thread pool, which the main thread can't wait on, so the
+/// reader lives in the worker and is driven by the `CsvBatchReader` of `io.ts`.
+#[wasm_bindgen(js_name = CsvBatchReader)]
+pub struct JsCsvBatchReader {
+    compression: String,
+    decoder: Option<ChunkedDecoder>,
+    text_decoder: ChunkedTextDecoder,
+    encoding: CsvEncoding,
+    pending: Vec<u8>,
+    buffered: Option<DataFrame>,
+    schema: Option<SchemaRef>,
+    batch_size: usize,
+    has_header: bool,
+    delimiter: u8,
+    quote_char: Option<u8>,
+    eol_char: u8,
+    infer_schema_length: Option<usize>,
+    ignore_errors: bool,
+}
+
+#[wasm_bindgen(js_class = CsvBatchReader)]
+impl JsCsvBatchReader {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        batch_size: u32,
+        has_header: bool,
+        sep: String,
+        quote_char: Option<String>,
+        eol_char: String,
+        infer_schema_length: Option<u32>,
+        ignore_errors: bool,
+        compression: String,
+        encoding: String,
+    ) -> JsResult<JsCsvBatchReader> {
+        if batch_size == 0 {
+            return Err(JsPolarsErr::Other("batch_size must be greater than 0".into()).into());
+        }
+        let quote_char = quote_char
+            .map(|q| str_to_byte(&q, "quote_char"))
+            .transpose()?;
+        let encoding = TextEncoding::from_option(&encoding)?;
+        Ok(JsCsvBatchReader {
+            compression,
+            decoder: None,
+            text_decoder: ChunkedTextDecoder::new(encoding),
+            encoding: encoding.csv_encoding(),
+            pending: Vec::new(),
+            buffered: None,
+            schema: None,
+            batch_size: batch_size as usize,
+            has_header,
+            delimiter: str_to_byte(&sep, "sep")?,
+            quote_char,
+            eol_char: str_to_byte(&eol_char, "eol_char")?,
+            infer_schema_length: infer_schema_length.map(|i| i as usize),
+            ignore_errors,
+        })
+    }
+
+    /// The schema locked after the first batch, as an object of `{column: dtype}`.
+    #[wasm_bindgen(getter)]
+    pub fn schema(&self) -> JsResult<JsValue> {
+        match &self.schema {
+            None => Ok(JsValue::null()),
+            Some(schema) => schema_to_js(schema),
+        }
+    }
+
+    /// Push the next chunk of bytes.
+    /// @returns every full batch of `batch_size` rows that could be completed
+    pub fn push(&mut self, chunk: &[u8]) -> JsResult<js_sys::Array> {
+        self.push_bytes(chunk)?;
+        Ok(self.take_batches(false))
+    }
+
+    /// Parse the remaining bytes that are not followed by a line terminator.
+    /// @returns the remaining batches, the last one may hold less than `batch_size` rows
+    pub fn finish(&mut self) -> JsResult<js_sys::Array> {
+        self.finish_bytes()?;
+        Ok(self.take_batches(true))
+    }
+}
+
+impl JsCsvBatchReader {
+    fn parse(&mut self, lines: &[u8]) -> JsResult<()> {
+        let reader = CsvReader::new(Cursor::new(lines))
+            .with_delimiter(self.delimiter)
+            .with_quote_char(self.quote_char)
+            .with_end_of_line_char(self.eol_char)
+            .with_ignore_parser_errors(self.ignore_errors)
+            .with_encoding(self.encoding);
+
+        let df = match &self.schema {
+            None => {
+                let df = reader
+                    .has_header(self.has_header)
+                    .infer_schema(self.infer_schema_length)
+                    .finish()
+                    .map_err(JsPolarsErr::from)?;
+                self.schema = Some(Arc::new(df.schema()));
+                df
+            }
+            Some(schema) => reader
+                .has_header(false)
+                .with_schema(schema.clone())
+                .finish()
+                .map_err(JsPolarsErr::from)?,
+        };
+
+        match self.buffered.as_mut() {
+            None => self.buffered = Some(df),
+            Some(buffered) => {
+                buffered.vstack_mut(&df).map_err(JsPolarsErr::from)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn push_bytes(&mut self, chunk: &[u8]) -> JsResult<()> {
+        if self.decoder.is_none() {
+            let compression = Compression::from_option(&self.compression, chunk)?;
+            self.decoder = Some(ChunkedDecoder::new(compression)?);
+        }
+        let mut decompressed = Vec::new();
+        self.decoder
+            .as_mut()
+            .unwrap()
+            .decode(chunk, &mut decompressed)?;
+        self.text_decoder
+            .decode(&decompressed, &mut self.pending, false);
+
+        if let Some(end) = last_line_end(&self.pending, self.quote_char, self.eol_char) {
+            // wait for a data row before inferring the schema from the header only
+            let waiting_for_data = self.schema.is_none()
+                && self.has_header
+                && self.pending[..end].iter().all(|b| *b != self.eol_char);
+            if !waiting_for_data {
+                let lines: Vec<u8> = self.pending.drain(..=end).collect();
+                self.parse(&lines)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish_bytes(&mut self) -> JsResult<()> {
+        let mut decompressed = Vec::new();
+        if let Some(decoder) = self.decoder.as_mut() {
+            decoder.finish(&mut decompressed)?;
+        }
+        self.text_decoder
+            .decode(&decompressed, &mut self.pending, true);
+        let lines = std::mem::take(&mut self.pending);
+        if lines.iter().any(|b| !b.is_ascii_whitespace()) {
+            self.parse(&lines)?;
+        }
+        Ok(())
+    }
+
+    /// The next batch of `batch_size` rows, or with `flush` the rows that are left.
+    fn next_batch(&mut self, flush: bool) -> Option<DataFrame> {
+        let df = self.buffered.take()?;
+        let height = df.height();
+        if height > self.batch_size {
+            self.buffered = Some(df.slice(self.batch_size as i64, height - self.batch_size));
+            Some(df.slice(0, self.batch_size).agg_chunks())
+        } else if height == self.batch_size || (flush && height > 0) {
+            Some(df.agg_chunks())
+        } else {
+            if height > 0 {
+                self.buffered = Some(df);
+            }
+            None
+        }
+    }
+
+    fn take_batches(&mut self, flush: bool) -> js_sys::Array {
+        let batches = js_sys::Array::new();
+        while let Some(df) = self.next_batch(flush) {
+            batches.push(&JsDataFrame::from(df).into());
+        }
+        batches
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn reader(batch_size: u32) -> JsCsvBatchReader {
+        reader_with_compression(batch_size, "uncompressed")
+    }
+
+    fn reader_with_compression(batch_size: u32, compression: &str) -> JsCsvBatchReader {
+        let quote = Some("\"".to_string());
+        JsCsvBatchReader::new(
+            batch_size,
+            true,
+            ",".into(),
+            quote,
+            "\n".into(),
+            Some(100),
+            false,
+            compression.into(),
+            "utf8".into(),
+        )
+        .unwrap()
+    }
+
+    fn read_all(reader: &mut JsCsvBatchReader, chunks: &[&[u8]]) -> Vec<DataFrame> {
+        let mut batches = vec![];
+        for chunk in chunks {
+            reader.push_bytes(chunk).unwrap();
+            batches.extend(std::iter::from_fn(|| reader.next_batch(false)));
+        }
+        reader.finish_bytes().unwrap();
+        batches.extend(std::iter::from_fn(|| reader.next_batch(true)));
+        batches
+    }
+
+    #[test]
+    fn test_last_line_end_skips_quoted_newlines() {
+        let buf = b"a,\"x\ny\"\nb,\"p\nq";
+        assert_eq!(last_line_end(buf, Some(b'"'), b'\n'), Some(7));
+        assert_eq!(last_line_end(buf, None, b'\n'), Some(12));
+        assert_eq!(last_line_end(b"a,b", Some(b'"'), b'\n'), None);
+    }
+
+    #[test]
+    fn test_quoted_newline_split_across_chunks() {
+        let mut reader = reader(10);
+        let batches = read_all(&mut reader, &[b"a,b\n1,\"x\n", b"y\"\n2,z\n"]);
+        assert_eq!(batches.len(), 1);
+        let b = batches[0].column("b").unwrap();
+        assert_eq!(b.utf8().unwrap().get(0), Some("x\ny"));
+        assert_eq!(b.utf8().unwrap().get(1), Some("z"));
+    }
+
+    #[test]
+    fn test_header_only_first_chunk() {
+        let mut reader = reader(10);
+        let batches = read_all(&mut reader, &[b"a,b\n", b"1,2\n3,4\n"]);
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].height(), 2);
+        assert_eq!(batches[0].column("a").unwrap().dtype(), &DataType::Int64);
+    }
+
+    #[test]
+    fn test_exact_multiple_of_batch_size() {
+        let mut reader = reader(2);
+        let batches = read_all(&mut reader, &[b"a\n1\n2\n", b"3\n4\n"]);
+        let heights: Vec<usize> = batches.iter().map(|df| df.height()).collect();
+        assert_eq!(heights, vec![2, 2]);
+    }
+
+    #[test]
+    fn test_zstd_chunks() {
+        // a single segment frame holding one raw block of 6 bytes
+        let mut frame = vec![0x28, 0xb5, 0x2f, 0xfd, 0x20, 0x06, 0x31, 0x00, 0x00];
+        frame.extend_from_slice(b"a\n1\n2\n");
+        let mut reader = reader_with_compression(10, "zstd");
+        let batches = read_all(&mut reader, &[&frame[..5], &frame[5..]]);
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].height(), 2);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{dataframe::JsDataFrame, error::JsPolarsErr, JsResult};
+use polars::prelude::*;
+use rayon::prelude::*;
+use std::io::Cursor;
+
+use wasm_bindgen::prelude::*;
+
+use super::compression::decompress;
+use super::encoding::TextEncoding;
+use super::vfs::InputBytes;
+use super::{js_to_null_values, str_to_byte};
+
+/// How the frames read by `read_csv_many` are combined.
+#[derive(Clone, Copy, PartialEq)]
+enum ConcatMethod {
+    /// All files have the same columns in the same order.
+    Vertical,
+    /// Columns are matched by name, missing columns are filled with nulls.
+    Diagonal,
+}
+
+impl ConcatMethod {
+    fn from_option(how: &str) -> JsResult<Self> {
+        match how {
+            "vertical" => Ok(ConcatMethod::Vertical),
+            "diagonal" => Ok(ConcatMethod::Diagonal),
+            s => Err(JsPolarsErr::Other(format!(
+                "how should be one of vertical, diagonal, got {}",
+                s
+            ))
+            .into()),
+        }
+    }
+}
+
+/// Union of the schemas in order of first appearance, upcasting columns that have
+/// different dtypes across frames to their supertype.
+fn unify_schemas(dfs: &[DataFrame], how: ConcatMethod) -> JsResult<Schema> {
+    let mut schema = Schema::new();
+    for (idx, df) in dfs.iter().enumerate() {
+        if how == ConcatMethod::Vertical
+            && idx > 0
+            && df.get_column_names() != dfs[0].get_column_names()
+        {
+            return Err(JsPolarsErr::Other(format!(
+                "columns of file {} don't match the columns of the first file, use how: \"diagonal\"",
+                idx
+            ))
+            .into());
+        }
+        for s in df.get_columns() {
+            let dtype = match schema.get(s.name()) {
+                Some(dtype) => get_supertype(dtype, s.dtype()).map_err(JsPolarsErr::from)?,
+                None => s.dtype().clone(),
+            };
+            schema.with_column(s.name().to_string(), dtype);
+        }
+    }
+    Ok(schema)
+}
+
+/// Cast the columns of `df` to `schema`, in the order of `schema`,
+/// adding the columns it's missing as nulls.
+fn align_to_schema(df: &DataFrame, schema: &Schema) -> PolarsResult<DataFrame> {
+    let columns = schema
+        .iter()
+        .map(|(name, dtype)| match df.column(name) {
+            Ok(s) if s.dtype() == dtype => Ok(s.clone()),
+            Ok(s) => s.cast(dtype),
+            Err(_) => Ok(Series::full_null(name, df.height(), dtype)),
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    DataFrame::new(columns)
+}
+
+/// Read many CSV files into one DataFrame, parsing them in parallel.
+/// Columns whose dtype differs between files are upcast to a common dtype.
+/// @param buffers - an array of `Uint8Array` or paths of registered files, one per file
+/// @param how - `vertical` if all files have the same columns,
+/// `diagonal` to match columns by name and fill the ones a file is missing with nulls
+/// @param source_column - if set, adds a column with the index of the file every row comes from
+#[wasm_bindgen]
+pub fn read_csv_many(
+    buffers: js_sys::Array,
+    how: &str,
+    source_column: Option<String>,
+    infer_schema_length: Option<u32>,
+    has_header: bool,
+    ignore_errors: bool,
+    parse_dates: bool,
+    sep: String,
+    quote_char: Option<String>,
+    eol_char: String,
+    null_values: JsValue,
+    rechunk: bool,
+    compression: &str,
+    encoding: String,
+) -> JsResult<JsDataFrame> {
+    let how = ConcatMethod::from_option(how)?;
+    let infer_schema_length = infer_schema_length.map(|i| i as usize);
+    let sep = str_to_byte(&sep, "sep")?;
+    let quote_char = quote_char
+        .map(|q| str_to_byte(&q, "quote_char"))
+        .transpose()?;
+    let eol_char = str_to_byte(&eol_char, "eol_char")?;
+    let null_values = js_to_null_values(null_values)?;
+    let encoding = TextEncoding::from_option(&encoding)?;
+
+    // JS buffers can't be shared with the thread pool, so they are copied (and decompressed) first
+    let buffers = buffers
+        .iter()
+        .map(|buf| {
+            let buf = InputBytes::from_js(buf)?;
+            let buf = decompress(&buf, compression)?;
+            Ok(encoding.decode(&buf).into_owned())
+        })
+        .collect::<JsResult<Vec<Vec<u8>>>>()?;
+
+    let dfs = buffers
+        .into_par_iter()
+        .enumerate()
+        .map(|(idx, buf)| {
+            let mut df = CsvReader::new(Cursor::new(buf))
+                .infer_schema(infer_schema_length)
+                .has_header(has_header)
+                .with_ignore_parser_errors(ignore_errors)
+                .with_parse_dates(parse_dates)
+                .with_delimiter(sep)
+                .with_quote_char(quote_char)
+                .with_end_of_line_char(eol_char)
+                .with_null_values(null_values.clone())
+                .with_encoding(encoding.csv_encoding())
+                .finish()?;
+            if let Some(name) = &source_column {
+                let source = Series::new(name, vec![idx as u32; df.height()]);
+                df.insert_at_idx(0, source)?;
+            }
+            Ok(df)
+        })
+        .collect::<PolarsResult<Vec<_>>>()
+        .map_err(JsPolarsErr::from)?;
+
+    let schema = unify_schemas(&dfs, how)?;
+    let mut dfs = dfs.iter().map(|df| align_to_schema(df, &schema));
+    let mut df = match dfs.next() {
+        Some(df) => df.map_err(JsPolarsErr::from)?,
+        None => DataFrame::default(),
+    };
+    for other in dfs {
+        df.vstack_mut(&other.map_err(JsPolarsErr::from)?)
+            .map_err(JsPolarsErr::from)?;
+    }
+    if rechunk {
+        df.rechunk();
+    }
+
+    Ok(df.into())
+}
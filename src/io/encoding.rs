@@ -0,0 +1,101 @@
+use crate::{error::JsPolarsErr, JsResult};
+use encoding_rs::{Decoder, Encoding, UTF_8};
+use polars::prelude::CsvEncoding;
+use std::borrow::Cow;
+
+const UTF8_BOM: [u8; 3] = [0xef, 0xbb, 0xbf];
+
+/// Text encoding of CSV input.
+/// Anything but UTF-8 is transcoded to UTF-8 inside wasm before parsing.
+#[derive(Clone, Copy)]
+pub(crate) enum TextEncoding {
+    Utf8,
+    LossyUtf8,
+    Other(&'static Encoding),
+}
+
+impl TextEncoding {
+    /// @param encoding - `utf8`, `utf8-lossy` or any WHATWG encoding label,
+    /// e.g. `windows-1252`, `latin1` or `utf-16le`
+    pub(crate) fn from_option(encoding: &str) -> JsResult<TextEncoding> {
+        match encoding {
+            "utf8" => Ok(TextEncoding::Utf8),
+            "utf8-lossy" => Ok(TextEncoding::LossyUtf8),
+            label => match Encoding::for_label(label.as_bytes()) {
+                Some(enc) if enc == UTF_8 => Ok(TextEncoding::Utf8),
+                Some(enc) => Ok(TextEncoding::Other(enc)),
+                None => Err(
+                    JsPolarsErr::Other(format!("encoding not {} not implemented.", label)).into(),
+                ),
+            },
+        }
+    }
+
+    /// The encoding the CSV parser sees after `decode`.
+    pub(crate) fn csv_encoding(&self) -> CsvEncoding {
+        match self {
+            TextEncoding::LossyUtf8 => CsvEncoding::LossyUtf8,
+            _ => CsvEncoding::Utf8,
+        }
+    }
+
+    /// Transcode `buf` to UTF-8 and strip a leading byte order mark.
+    pub(crate) fn decode<'a>(&self, buf: &'a [u8]) -> Cow<'a, [u8]> {
+        match self {
+            TextEncoding::Utf8 | TextEncoding::LossyUtf8 => {
+                Cow::Borrowed(buf.strip_prefix(&UTF8_BOM).unwrap_or(buf))
+            }
+            // `Encoding::decode` also sniffs and removes BOMs, so UTF-16 input
+            // is decoded with the endianness its BOM declares
+            TextEncoding::Other(enc) => {
+                let (text, _, _) = enc.decode(buf);
+                Cow::Owned(text.into_owned().into_bytes())
+            }
+        }
+    }
+}
+
+/// Transcodes text that arrives in chunks, carrying partial characters to the next chunk.
+pub(crate) struct ChunkedTextDecoder {
+    decoder: Option<Decoder>,
+    at_start: bool,
+}
+
+impl ChunkedTextDecoder {
+    pub(crate) fn new(encoding: TextEncoding) -> ChunkedTextDecoder {
+        let decoder = match encoding {
+            TextEncoding::Other(enc) => Some(enc.new_decoder()),
+            _ => None,
+        };
+        ChunkedTextDecoder {
+            decoder,
+            at_start: true,
+        }
+    }
+
+    /// Decode `chunk` and append the UTF-8 bytes to `out`.
+    pub(crate) fn decode(&mut self, chunk: &[u8], out: &mut Vec<u8>, last: bool) {
+        match self.decoder.as_mut() {
+            None => {
+                let chunk = if self.at_start {
+                    chunk.strip_prefix(&UTF8_BOM).unwrap_or(chunk)
+                } else {
+                    chunk
+                };
+                out.extend_from_slice(chunk);
+            }
+            Some(decoder) => {
+                let mut text = String::with_capacity(
+                    decoder
+                        .max_utf8_buffer_length(chunk.len())
+                        .unwrap_or(chunk.len() * 3),
+                );
+                let _ = decoder.decode_to_string(chunk, &mut text, last);
+                out.extend_from_slice(text.as_bytes());
+            }
+        }
+        if !chunk.is_empty() {
+            self.at_start = false;
+        }
+    }
+}
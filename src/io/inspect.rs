@@ -0,0 +1,182 @@
+use crate::conversion::Wrap;
+use crate::{error::JsPolarsErr, JsResult};
+use polars::export::arrow::io::ipc::read::read_file_metadata;
+use polars::export::arrow::io::parquet::read::{self, statistics};
+use polars::prelude::*;
+use std::io::Cursor;
+
+use wasm_bindgen::prelude::*;
+
+use super::compression::decompress;
+use super::encoding::TextEncoding;
+use super::ipc_stream::CONTINUATION_MARKER;
+use super::vfs::InputBytes;
+use super::{schema_to_js, str_to_byte};
+
+fn set(obj: &js_sys::Object, key: &str, value: &JsValue) -> JsResult<()> {
+    js_sys::Reflect::set(obj, &key.into(), value)?;
+    Ok(())
+}
+
+/// `{schema, numRows}`, with `numRows` set to `null` when it isn't known without parsing.
+fn schema_info(schema: &Schema, num_rows: Option<usize>) -> JsResult<js_sys::Object> {
+    let obj = js_sys::Object::new();
+    set(&obj, "schema", &schema_to_js(schema)?)?;
+    let num_rows = num_rows.map_or(JsValue::null(), |n| (n as f64).into());
+    set(&obj, "numRows", &num_rows)?;
+    Ok(obj)
+}
+
+/// Infer the schema of a CSV file from its first rows, without reading the whole file.
+/// @returns `{schema, numRows}`, `numRows` is always `null` for CSV
+#[wasm_bindgen]
+pub fn read_csv_schema(
+    buff: JsValue,
+    infer_schema_length: Option<u32>,
+    has_header: bool,
+    sep: String,
+    quote_char: Option<String>,
+    eol_char: String,
+    compression: &str,
+    encoding: String,
+) -> JsResult<JsValue> {
+    let infer_schema_length = infer_schema_length.map(|i| i as usize);
+    let quote_char = quote_char
+        .map(|q| str_to_byte(&q, "quote_char"))
+        .transpose()?;
+    let encoding = TextEncoding::from_option(&encoding)?;
+
+    let buff = InputBytes::from_js(buff)?;
+    let buff = decompress(&buff, compression)?;
+    let buff = encoding.decode(&buff);
+    let df = CsvReader::new(Cursor::new(buff.as_ref()))
+        .has_header(has_header)
+        .with_delimiter(str_to_byte(&sep, "sep")?)
+        .with_quote_char(quote_char)
+        .with_end_of_line_char(str_to_byte(&eol_char, "eol_char")?)
+        .with_encoding(encoding.csv_encoding())
+        .infer_schema(infer_schema_length)
+        .with_n_rows(infer_schema_length)
+        .finish()
+        .map_err(JsPolarsErr::from)?;
+
+    Ok(schema_info(&df.schema(), None)?.into())
+}
+
+/// Read the schema, row count and row group statistics from the footer of a Parquet file.
+/// @returns `{schema, numRows, rowGroups}`, where every row group is
+/// `{numRows, totalByteSize, columns: {column: {nullCount, min, max}}}`.
+/// Statistics that are missing from the file or can't be read are `null`.
+#[wasm_bindgen]
+pub fn read_parquet_metadata(buff: JsValue, compression: &str) -> JsResult<JsValue> {
+    let buff = InputBytes::from_js(buff)?;
+    let buff = decompress(&buff, compression)?;
+    let mut cursor = Cursor::new(buff.as_ref());
+    let metadata = read::read_metadata(&mut cursor).map_err(JsPolarsErr::from)?;
+    let arrow_schema = read::infer_schema(&metadata).map_err(JsPolarsErr::from)?;
+    let schema = Schema::from(arrow_schema.fields.iter().map(Field::from));
+
+    let row_groups: Vec<js_sys::Object> = metadata
+        .row_groups
+        .iter()
+        .map(|rg| {
+            let obj = js_sys::Object::new();
+            set(&obj, "numRows", &(rg.num_rows() as f64).into())?;
+            set(&obj, "totalByteSize", &(rg.total_byte_size() as f64).into())?;
+            set(&obj, "columns", &js_sys::Object::new())?;
+            Ok(obj)
+        })
+        .collect::<JsResult<_>>()?;
+
+    for field in &arrow_schema.fields {
+        // statistics of nested columns are nested as well, only report them for leaf columns
+        let nested = matches!(
+            DataType::from(&field.data_type),
+            DataType::List(_) | DataType::Struct(_)
+        );
+        // statistics that can't be read are reported as missing rather than failing the call
+        let stats = statistics::deserialize(field, &metadata.row_groups)
+            .ok()
+            .filter(|_| !nested);
+        let (null_count, min, max) = match stats {
+            Some(stats) => (
+                Series::try_from(("null_count", stats.null_count))
+                    .and_then(|s| s.cast(&DataType::Float64))
+                    .ok(),
+                Series::try_from(("min", stats.min_value)).ok(),
+                Series::try_from(("max", stats.max_value)).ok(),
+            ),
+            None => (None, None, None),
+        };
+
+        for (idx, rg) in row_groups.iter().enumerate() {
+            let value = |s: &Option<Series>| -> JsValue {
+                match s.as_ref().and_then(|s| s.get(idx).ok()) {
+                    Some(av) => Wrap(av).into(),
+                    None => JsValue::null(),
+                }
+            };
+            let col = js_sys::Object::new();
+            set(&col, "nullCount", &value(&null_count))?;
+            set(&col, "min", &value(&min))?;
+            set(&col, "max", &value(&max))?;
+            let columns = js_sys::Reflect::get(rg, &"columns".into())?;
+            js_sys::Reflect::set(&columns, &field.name.as_str().into(), &col)?;
+        }
+    }
+
+    let obj = schema_info(&schema, Some(metadata.num_rows))?;
+    set(
+        &obj,
+        "rowGroups",
+        &row_groups.into_iter().collect::<js_sys::Array>(),
+    )?;
+    Ok(obj.into())
+}
+
+/// Number of rows of the record batch message at the start of `buf`.
+fn record_batch_len(buf: &[u8]) -> JsResult<usize> {
+    let invalid =
+        |e: &dyn std::fmt::Display| JsPolarsErr::Other(format!("invalid IPC file: {}", e));
+    let prefix = if buf.get(..4) == Some(&CONTINUATION_MARKER[..]) {
+        8
+    } else {
+        4
+    };
+    let message = buf
+        .get(prefix..)
+        .ok_or_else(|| invalid(&"truncated record batch"))
+        .and_then(|buf| {
+            arrow_format::ipc::MessageRef::read_as_root(buf).map_err(|e| invalid(&e))
+        })?;
+    match message.header().map_err(|e| invalid(&e))? {
+        Some(arrow_format::ipc::MessageHeaderRef::RecordBatch(batch)) => {
+            Ok(batch.length().map_err(|e| invalid(&e))? as usize)
+        }
+        _ => Err(invalid(&"expected a record batch").into()),
+    }
+}
+
+/// Read the schema and row count of an Arrow IPC file from its footer
+/// and record batch headers, without reading the data.
+/// @returns `{schema, numRows}`
+#[wasm_bindgen]
+pub fn read_ipc_schema(buff: JsValue, compression: &str) -> JsResult<JsValue> {
+    let buff = InputBytes::from_js(buff)?;
+    let buff = decompress(&buff, compression)?;
+    let metadata =
+        read_file_metadata(&mut Cursor::new(buff.as_ref())).map_err(JsPolarsErr::from)?;
+    let schema = Schema::from(metadata.schema.fields.iter().map(Field::from));
+
+    let mut num_rows = 0;
+    for block in &metadata.blocks {
+        let start = block.offset as usize;
+        let end = start + block.meta_data_length as usize;
+        let header = buff.get(start..end).ok_or_else(|| {
+            JsPolarsErr::Other("invalid IPC file: record batch out of bounds".into())
+        })?;
+        num_rows += record_batch_len(header)?;
+    }
+
+    Ok(schema_info(&schema, Some(num_rows))?.into())
+}
@@ -0,0 +1,248 @@
+use crate::{dataframe::JsDataFrame, error::JsPolarsErr, JsResult};
+use polars::export::arrow::datatypes::{Field as ArrowField, Schema as ArrowSchema};
+use polars::export::arrow::io::ipc::read::{read_stream_metadata, StreamReader, StreamState};
+use polars::export::arrow::io::ipc::write::{StreamWriter, WriteOptions};
+use polars::prelude::*;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+
+use super::str_to_ipc_compression;
+
+/// Bytes of complete IPC messages, shared between the reader and the arrow `StreamReader`.
+///
+/// Only whole messages are ever moved in here, so the `StreamReader` either reads a
+/// full message or hits EOF on a message boundary and reports `StreamState::Waiting`.
+#[derive(Clone, Default)]
+struct MessageQueue(Rc<RefCell<VecDeque<u8>>>);
+
+impl Read for MessageQueue {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().read(buf)
+    }
+}
+
+/// Output buffer shared with the arrow `StreamWriter`, drained after every call.
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.borrow_mut())
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+pub(super) const CONTINUATION_MARKER: [u8; 4] = [0xff; 4];
+
+/// Length in bytes of the IPC message at the start of `buf`,
+/// or `None` if the message is not complete yet.
+fn message_len(buf: &[u8]) -> JsResult<Option<usize>> {
+    if buf.len() < 4 {
+        return Ok(None);
+    }
+    // messages written before arrow 0.15 don't have the continuation marker
+    let prefix = if buf[..4] == CONTINUATION_MARKER {
+        8
+    } else {
+        4
+    };
+    if buf.len() < prefix {
+        return Ok(None);
+    }
+    let meta_len = i32::from_le_bytes(buf[prefix - 4..prefix].try_into().unwrap());
+    if meta_len <= 0 {
+        // end of stream marker
+        return Ok(Some(prefix));
+    }
+    let meta_end = prefix + meta_len as usize;
+    if buf.len() < meta_end {
+        return Ok(None);
+    }
+    let message = arrow_format::ipc::MessageRef::read_as_root(&buf[prefix..meta_end])
+        .map_err(|e| JsPolarsErr::Other(format!("invalid IPC message: {}", e)))?;
+    let body_len = message
+        .body_length()
+        .map_err(|e| JsPolarsErr::Other(format!("invalid IPC message: {}", e)))?;
+    let total = meta_end + body_len as usize;
+    if buf.len() < total {
+        Ok(None)
+    } else {
+        Ok(Some(total))
+    }
+}
+
+/// Incrementally decode an Arrow IPC stream.
+/// @example
+/// ```js
+/// > const reader = new pl.IpcStreamReader()
+/// > for await (const chunk of response.body) {
+/// ...   for (const df of reader.push(chunk)) {
+/// ...     console.log(df.height())
+/// ...   }
+/// ... }
+/// ```
+#[wasm_bindgen(js_name = IpcStreamReader)]
+pub struct JsIpcStreamReader {
+    pending: Vec<u8>,
+    messages: MessageQueue,
+    fields: Vec<ArrowField>,
+    reader: Option<StreamReader<MessageQueue>>,
+    finished: bool,
+}
+
+impl Default for JsIpcStreamReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen(js_class = IpcStreamReader)]
+impl JsIpcStreamReader {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        JsIpcStreamReader {
+            pending: Vec::new(),
+            messages: MessageQueue::default(),
+            fields: Vec::new(),
+            reader: None,
+            finished: false,
+        }
+    }
+
+    /// Whether the end of stream marker has been read.
+    #[wasm_bindgen(getter)]
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Push the next chunk of bytes of the stream.
+    /// @returns a DataFrame for every record batch completed by this chunk
+    pub fn push(&mut self, chunk: &[u8]) -> JsResult<js_sys::Array> {
+        let batches = js_sys::Array::new();
+        if self.finished {
+            return Ok(batches);
+        }
+        self.pending.extend_from_slice(chunk);
+
+        let mut offset = 0;
+        while let Some(len) = message_len(&self.pending[offset..])? {
+            offset += len;
+        }
+        if offset == 0 {
+            return Ok(batches);
+        }
+        self.messages
+            .0
+            .borrow_mut()
+            .extend(self.pending.drain(..offset));
+
+        if self.reader.is_none() {
+            let metadata =
+                read_stream_metadata(&mut self.messages.clone()).map_err(JsPolarsErr::from)?;
+            self.fields = metadata.schema.fields.clone();
+            self.reader = Some(StreamReader::new(self.messages.clone(), metadata, None));
+        }
+        let reader = self.reader.as_mut().unwrap();
+
+        loop {
+            match reader.next() {
+                None => {
+                    self.finished = true;
+                    break;
+                }
+                Some(state) => match state.map_err(JsPolarsErr::from)? {
+                    StreamState::Some(chunk) => {
+                        let df = DataFrame::try_from((chunk, self.fields.as_slice()))
+                            .map_err(JsPolarsErr::from)?;
+                        batches.push(&JsDataFrame::from(df).into());
+                    }
+                    StreamState::Waiting => break,
+                },
+            }
+        }
+        Ok(batches)
+    }
+}
+
+/// Incrementally encode DataFrames as an Arrow IPC stream.
+/// The first call to `write` also emits the schema message.
+#[wasm_bindgen(js_name = IpcStreamWriter)]
+pub struct JsIpcStreamWriter {
+    buf: SharedBuffer,
+    writer: StreamWriter<SharedBuffer>,
+    schema: Option<ArrowSchema>,
+}
+
+#[wasm_bindgen(js_class = IpcStreamWriter)]
+impl JsIpcStreamWriter {
+    /// @param compression - one of `uncompressed`, `lz4` or `zstd`
+    #[wasm_bindgen(constructor)]
+    pub fn new(compression: &str) -> JsResult<JsIpcStreamWriter> {
+        let compression = str_to_ipc_compression(compression)?;
+        let buf = SharedBuffer::default();
+        let writer = StreamWriter::new(
+            buf.clone(),
+            WriteOptions {
+                compression: compression.map(|c| c.into()),
+            },
+        );
+        Ok(JsIpcStreamWriter {
+            buf,
+            writer,
+            schema: None,
+        })
+    }
+
+    /// Encode the DataFrame as record batch messages.
+    /// @returns the encoded messages
+    pub fn write(&mut self, df: &JsDataFrame) -> JsResult<Vec<u8>> {
+        let schema = df.df.schema().to_arrow();
+        match &self.schema {
+            None => {
+                self.writer
+                    .start(&schema, None)
+                    .map_err(JsPolarsErr::from)?;
+                self.schema = Some(schema);
+            }
+            Some(expected) if expected != &schema => {
+                return Err(JsPolarsErr::Other(
+                    "DataFrame schema does not match the schema of the stream".into(),
+                )
+                .into())
+            }
+            Some(_) => {}
+        }
+
+        let mut df = df.df.clone();
+        df.align_chunks();
+        for chunk in df.iter_chunks() {
+            self.writer.write(&chunk, None).map_err(JsPolarsErr::from)?;
+        }
+        Ok(self.buf.take())
+    }
+
+    /// Write the end of stream marker.
+    /// @returns the remaining bytes of the stream
+    pub fn finish(&mut self) -> JsResult<Vec<u8>> {
+        if self.schema.is_none() {
+            return Err(
+                JsPolarsErr::Other("cannot finish a stream without a schema".into()).into(),
+            );
+        }
+        self.writer.finish().map_err(JsPolarsErr::from)?;
+        Ok(self.buf.take())
+    }
+}
@@ -0,0 +1,713 @@
+mod compression;
+mod csv_batched;
+mod csv_many;
+mod encoding;
+mod inspect;
+mod ipc_stream;
+pub(crate) mod range;
+mod sink;
+mod sniff;
+mod vfs;
+
+pub use csv_batched::*;
+pub use csv_many::*;
+pub use inspect::*;
+pub use ipc_stream::*;
+pub use range::ByteRangeSource;
+pub(crate) use range::{recv_serving_reads, run_serving_reads, ForwardReads, Message, RangeReader};
+pub(crate) use sink::JsCallbackWriter;
+pub use vfs::{list_files, register_file, unregister_file};
+
+use super::{error::JsPolarsErr, JsResult};
+use crate::dataframe::JsDataFrame;
+use crate::datatypes::dtype_name;
+use crate::lazy::dataframe::JsLazyFrame;
+use crate::lazy::scan::{CsvBytesScan, CsvScanOptions, ParquetRangeScan};
+use crate::utils::{js_to_column_names, str_to_polarstype};
+use compression::decompress;
+use encoding::TextEncoding;
+use polars::prelude::*;
+use std::borrow::Cow;
+use std::io::Cursor;
+use vfs::InputBytes;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+#[wasm_bindgen]
+pub fn read_csv(
+    buff: JsValue,
+    infer_schema_length: Option<u32>,
+    chunk_size: u32,
+    has_header: Option<bool>,
+    ignore_errors: bool,
+    n_rows: Option<u32>,
+    skip_rows: u32,
+    rechunk: bool,
+    encoding: Option<String>,
+    n_threads: Option<u32>,
+    low_memory: bool,
+    parse_dates: bool,
+    skip_rows_after_header: usize,
+    sep: String,
+    quote_char: JsValue,
+    comment_char: Option<String>,
+    eol_char: Option<String>,
+    null_values: JsValue,
+    dtypes: JsValue,
+    columns: Option<js_sys::Array>,
+    projection: Option<Vec<u32>>,
+    row_count_name: Option<String>,
+    row_count_offset: u32,
+    compression: &str,
+) -> JsResult<JsDataFrame> {
+    let infer_schema_length = infer_schema_length.map(|i| i as usize);
+    let n_threads = n_threads.map(|i| i as usize);
+    let n_rows = n_rows.map(|i| i as usize);
+    let skip_rows = skip_rows as usize;
+    let chunk_size = chunk_size as usize;
+
+    let buff = InputBytes::from_js(buff)?;
+    let buff = decompress(&buff, compression)?;
+
+    // `sep: "auto"` takes the delimiter and whatever of the encoding, quoting, line endings
+    // and header isn't set from the sniffed dialect, `encoding: "auto"` the sniffed encoding
+    let dialect = match (sep.as_str(), encoding.as_deref()) {
+        ("auto", _) | (_, Some("auto")) => Some(sniff::sniff(&buff, SNIFF_SAMPLE_BYTES)?),
+        _ => None,
+    };
+    let encoding = match (&dialect, encoding.as_deref()) {
+        (Some(dialect), None | Some("auto")) => TextEncoding::from_option(dialect.encoding)?,
+        (_, encoding) => TextEncoding::from_option(encoding.unwrap_or("utf8"))?,
+    };
+    // `undefined` leaves the quote unset, `null` disables quoting
+    let quote_char = match quote_char {
+        q if q.is_undefined() => None,
+        q if q.is_null() => Some(None),
+        q => {
+            let q = q.as_string().ok_or_else(|| {
+                JsPolarsErr::Other("quote_char must be a string or null".to_string())
+            })?;
+            Some(Some(str_to_byte(&q, "quote_char")?))
+        }
+    };
+    let eol_char = eol_char.map(|c| str_to_byte(&c, "eol_char")).transpose()?;
+    let (delimiter, quote_char, eol_char, has_header) = match (&dialect, sep.as_str()) {
+        (Some(dialect), "auto") => (
+            dialect.delimiter,
+            quote_char.unwrap_or(dialect.quote_char),
+            eol_char.unwrap_or(dialect.eol_char),
+            has_header.unwrap_or(dialect.has_header),
+        ),
+        _ => (
+            str_to_byte(&sep, "sep")?,
+            quote_char.unwrap_or(Some(b'"')),
+            eol_char.unwrap_or(b'\n'),
+            has_header.unwrap_or(true),
+        ),
+    };
+    let comment_char = comment_char
+        .map(|c| str_to_byte(&c, "comment_char"))
+        .transpose()?;
+    let null_values = js_to_null_values(null_values)?;
+    let overwrite_dtype = js_to_schema(dtypes)?;
+    let columns = columns
+        .map(|cols| js_to_column_names(&cols, "columns"))
+        .transpose()?;
+    let projection = projection.map(|p| p.into_iter().map(|i| i as usize).collect());
+    let row_count = row_count_name.map(|name| RowCount {
+        name,
+        offset: row_count_offset as IdxSize,
+    });
+
+    let buff = encoding.decode(&buff);
+    let cursor = Cursor::new(buff.as_ref());
+    let df = CsvReader::new(cursor)
+        .infer_schema(infer_schema_length)
+        .has_header(has_header)
+        .with_n_rows(n_rows)
+        .with_delimiter(delimiter)
+        .with_skip_rows(skip_rows)
+        .with_ignore_parser_errors(ignore_errors)
+        .with_projection(projection)
+        .with_rechunk(rechunk)
+        .with_chunk_size(chunk_size)
+        .with_encoding(encoding.csv_encoding())
+        .with_columns(columns)
+        .with_dtypes(overwrite_dtype.as_ref())
+        .low_memory(low_memory)
+        .with_comment_char(comment_char)
+        .with_null_values(null_values)
+        .with_parse_dates(parse_dates)
+        .with_quote_char(quote_char)
+        .with_end_of_line_char(eol_char)
+        .with_n_threads(n_threads)
+        .with_skip_rows_after_header(skip_rows_after_header)
+        .with_row_count(row_count)
+        .finish()
+        .map_err(JsPolarsErr::from)?;
+
+    Ok(df.into())
+}
+
+/// Lazily read a CSV buffer.
+/// Projections of the query are pushed down into the parser, so only the selected
+/// columns are materialized. Predicates are applied to the rows once they are parsed.
+#[wasm_bindgen]
+pub fn scan_csv(
+    buff: JsValue,
+    infer_schema_length: Option<u32>,
+    has_header: bool,
+    ignore_errors: bool,
+    n_rows: Option<u32>,
+    skip_rows: u32,
+    parse_dates: bool,
+    sep: String,
+    quote_char: JsValue,
+    comment_char: Option<String>,
+    eol_char: Option<String>,
+    null_values: JsValue,
+    dtypes: JsValue,
+    compression: &str,
+    encoding: String,
+) -> JsResult<JsLazyFrame> {
+    let encoding = TextEncoding::from_option(&encoding)?;
+    let options = csv_scan_options(
+        has_header,
+        ignore_errors,
+        skip_rows,
+        parse_dates,
+        sep,
+        quote_char,
+        comment_char,
+        eol_char,
+        null_values,
+        dtypes,
+        &encoding,
+    )?;
+    let buff = InputBytes::from_js(buff)?;
+    let buff = decompress(&buff, compression)?;
+    let buff = encoding.decode(&buff).into_owned();
+    let args = ScanArgsAnonymous {
+        infer_schema_length: infer_schema_length.map(|i| i as usize),
+        n_rows: n_rows.map(|i| i as usize),
+        fmt_str: "CSV BYTES",
+        ..Default::default()
+    };
+    let ldf = LazyFrame::anonymous_scan(Arc::new(CsvBytesScan::new(buff, options)), args)
+        .map_err(JsPolarsErr::from)?;
+
+    Ok(ldf.into())
+}
+
+/// Lazily read the CSV files registered at `path` with `register_file`.
+/// @param path - a registered path, or a glob such as `data/year=*/part-*.csv`
+/// @param hive_partitioning - add the `key=value` directories of the paths as columns
+#[wasm_bindgen]
+pub fn scan_csv_path(
+    path: &str,
+    hive_partitioning: bool,
+    infer_schema_length: Option<u32>,
+    has_header: bool,
+    ignore_errors: bool,
+    n_rows: Option<u32>,
+    skip_rows: u32,
+    parse_dates: bool,
+    sep: String,
+    quote_char: JsValue,
+    comment_char: Option<String>,
+    eol_char: Option<String>,
+    null_values: JsValue,
+    dtypes: JsValue,
+    compression: &str,
+    encoding: String,
+) -> JsResult<JsLazyFrame> {
+    let encoding = TextEncoding::from_option(&encoding)?;
+    let options = csv_scan_options(
+        has_header,
+        ignore_errors,
+        skip_rows,
+        parse_dates,
+        sep,
+        quote_char,
+        comment_char,
+        eol_char,
+        null_values,
+        dtypes,
+        &encoding,
+    )?;
+    let files = vfs::resolve_files(path)?;
+    if files.is_empty() {
+        return Err(JsPolarsErr::Other(format!("no registered file matches '{}'", path)).into());
+    }
+
+    let partitions: Vec<Vec<(&str, &str)>> = files
+        .iter()
+        .map(|(path, _)| match hive_partitioning {
+            true => vfs::hive_partitions(path),
+            false => vec![],
+        })
+        .collect();
+    let keys: Vec<&str> = partitions[0].iter().map(|(key, _)| *key).collect();
+    if let Some((path, _)) = files
+        .iter()
+        .zip(&partitions)
+        .find(|(_, parts)| !parts.iter().map(|(key, _)| *key).eq(keys.iter().copied()))
+        .map(|(file, _)| file)
+    {
+        return Err(JsPolarsErr::Other(format!(
+            "partitions of '{}' don't match the partitions of '{}'",
+            path, files[0].0
+        ))
+        .into());
+    }
+    let partition_dtypes: Vec<DataType> = (0..keys.len())
+        .map(|idx| vfs::partition_dtype(partitions.iter().map(|parts| parts[idx].1)))
+        .collect();
+
+    let ldfs = files
+        .iter()
+        .zip(&partitions)
+        .map(|((_, file), parts)| {
+            let buf = csv_scan_bytes(file, compression, &encoding)?;
+            let args = ScanArgsAnonymous {
+                infer_schema_length: infer_schema_length.map(|i| i as usize),
+                fmt_str: "CSV BYTES",
+                ..Default::default()
+            };
+            let ldf =
+                LazyFrame::anonymous_scan(Arc::new(CsvBytesScan::new(buf, options.clone())), args)
+                    .map_err(JsPolarsErr::from)?;
+            let partition_columns: Vec<Expr> = parts
+                .iter()
+                .zip(&partition_dtypes)
+                .map(|((key, value), dtype)| vfs::partition_lit(value, dtype).alias(key))
+                .collect();
+            match partition_columns.is_empty() {
+                true => Ok(ldf),
+                false => Ok(ldf.with_columns(partition_columns)),
+            }
+        })
+        .collect::<JsResult<Vec<_>>>()?;
+
+    let mut ldf = concat(&ldfs, false, true).map_err(JsPolarsErr::from)?;
+    if let Some(n_rows) = n_rows {
+        ldf = ldf.limit(n_rows as IdxSize);
+    }
+
+    Ok(ldf.into())
+}
+
+/// The bytes a CSV scan of a registered file parses.
+fn csv_scan_bytes(
+    file: &Arc<Vec<u8>>,
+    compression: &str,
+    encoding: &TextEncoding,
+) -> JsResult<Arc<Vec<u8>>> {
+    let decompressed = decompress(file.as_slice(), compression)?;
+    let decoded = encoding.decode(&decompressed);
+    // plain UTF-8 files are scanned in place, without copying them. Stripping
+    // a byte order mark also borrows, but leaves a shorter slice to copy
+    let in_place = matches!(
+        (&decompressed, &decoded),
+        (Cow::Borrowed(_), Cow::Borrowed(_))
+    ) && decoded.len() == file.len();
+    Ok(match in_place {
+        true => file.clone(),
+        false => Arc::new(decoded.into_owned()),
+    })
+}
+
+fn csv_scan_options(
+    has_header: bool,
+    ignore_errors: bool,
+    skip_rows: u32,
+    parse_dates: bool,
+    sep: String,
+    quote_char: JsValue,
+    comment_char: Option<String>,
+    eol_char: Option<String>,
+    null_values: JsValue,
+    dtypes: JsValue,
+    encoding: &TextEncoding,
+) -> JsResult<CsvScanOptions> {
+    Ok(CsvScanOptions {
+        has_header,
+        ignore_errors,
+        skip_rows: skip_rows as usize,
+        parse_dates,
+        delimiter: str_to_byte(&sep, "sep")?,
+        quote_char: quote_char
+            .map(|q| str_to_byte(&q, "quote_char"))
+            .transpose()?,
+        comment_char: comment_char
+            .map(|c| str_to_byte(&c, "comment_char"))
+            .transpose()?,
+        eol_char: str_to_byte(&eol_char, "eol_char")?,
+        null_values: js_to_null_values(null_values)?,
+        overwrite_dtype: js_to_schema(dtypes)?,
+        encoding: encoding.csv_encoding(),
+    })
+}
+
+/// Lazily read a Parquet file through JS byte range requests.
+/// Only the footer is read up front, row groups and columns are read when the query
+/// is collected, skipping those that don't survive predicate and projection pushdown.
+/// The source stays on the worker, the reads the thread pool makes are answered by it
+/// while it collects the query.
+/// @param source - an object with `size()` and synchronous `read(offset, length)` methods
+#[wasm_bindgen(js_name = "__scan_parquet_from_worker", skip_typescript)]
+pub fn scan_parquet(source: ByteRangeSource) -> JsResult<JsLazyFrame> {
+    let reader = RangeReader::from_js(source)?;
+    let scan = ParquetRangeScan::try_new(reader).map_err(JsPolarsErr::from)?;
+    let args = ScanArgsAnonymous {
+        schema: Some(scan.polars_schema()),
+        fmt_str: "PARQUET RANGES",
+        ..Default::default()
+    };
+    let ldf = LazyFrame::anonymous_scan(Arc::new(scan), args).map_err(JsPolarsErr::from)?;
+
+    Ok(ldf.into())
+}
+
+const SNIFF_SAMPLE_BYTES: usize = 64 * 1024;
+
+/// Detect the dialect of a CSV file from a prefix of it.
+/// @param sample_bytes - number of bytes to inspect
+/// @returns an object of `{sep, quoteChar, hasHeader, eolChar, encoding, schema}`
+/// that can be passed on as options of `read_csv`
+#[wasm_bindgen]
+pub fn sniff_csv(buff: JsValue, sample_bytes: Option<u32>, compression: &str) -> JsResult<JsValue> {
+    let sample_bytes = sample_bytes.map_or(SNIFF_SAMPLE_BYTES, |n| n as usize);
+    let buff = InputBytes::from_js(buff)?;
+    let buff = decompress(&buff, compression)?;
+    let dialect = sniff::sniff(&buff, sample_bytes)?;
+    let schema = sniff::sniff_schema(&buff, sample_bytes, &dialect)?;
+
+    let byte_to_js = |b: u8| JsValue::from_str(&(b as char).to_string());
+    let obj = js_sys::Object::new();
+    js_sys::Reflect::set(&obj, &"sep".into(), &byte_to_js(dialect.delimiter))?;
+    js_sys::Reflect::set(
+        &obj,
+        &"quoteChar".into(),
+        &dialect.quote_char.map_or(JsValue::null(), byte_to_js),
+    )?;
+    js_sys::Reflect::set(&obj, &"hasHeader".into(), &dialect.has_header.into())?;
+    js_sys::Reflect::set(&obj, &"eolChar".into(), &byte_to_js(dialect.eol_char))?;
+    js_sys::Reflect::set(&obj, &"encoding".into(), &dialect.encoding.into())?;
+    js_sys::Reflect::set(&obj, &"schema".into(), &schema_to_js(&schema)?)?;
+    Ok(obj.into())
+}
+
+/// Convert a `Schema` to an object of `{column: dtype}`.
+pub(crate) fn schema_to_js(schema: &Schema) -> JsResult<JsValue> {
+    let obj = js_sys::Object::new();
+    for (name, dtype) in schema.iter() {
+        js_sys::Reflect::set(&obj, &name.as_str().into(), &dtype_name(dtype).into())?;
+    }
+    Ok(obj.into())
+}
+
+/// Get the single byte of a one character option such as `sep` or `quote_char`.
+pub(crate) fn str_to_byte(s: &str, option: &str) -> JsResult<u8> {
+    match s.as_bytes() {
+        [b] => Ok(*b),
+        _ => Err(JsPolarsErr::Other(format!(
+            "{} must be a single byte character, got '{}'",
+            option, s
+        ))
+        .into()),
+    }
+}
+
+/// Null value markers can be given as a single string for all columns,
+/// an array of strings for all columns, or an object of `{column: marker}`.
+pub(crate) fn js_to_null_values(v: JsValue) -> JsResult<Option<NullValues>> {
+    if v.is_null() || v.is_undefined() {
+        return Ok(None);
+    }
+    if let Some(s) = v.as_string() {
+        return Ok(Some(NullValues::AllColumnsSingle(s)));
+    }
+    if js_sys::Array::is_array(&v) {
+        let arr: js_sys::Array = v.unchecked_into();
+        let values = arr
+            .iter()
+            .map(|v| {
+                v.as_string().ok_or_else(|| {
+                    JsPolarsErr::Other("null values must be strings".to_string()).into()
+                })
+            })
+            .collect::<JsResult<_>>()?;
+        return Ok(Some(NullValues::AllColumns(values)));
+    }
+    let obj: js_sys::Object = v.unchecked_into();
+    let named = js_sys::Object::entries(&obj)
+        .iter()
+        .map(|entry| {
+            let entry: js_sys::Array = entry.unchecked_into();
+            let column = entry.get(0).as_string().unwrap();
+            let marker = entry.get(1).as_string().ok_or_else(|| {
+                JsPolarsErr::Other(format!("null value for {} must be a string", column))
+            })?;
+            Ok((column, marker))
+        })
+        .collect::<Result<Vec<_>, JsPolarsErr>>()?;
+    Ok(Some(NullValues::Named(named)))
+}
+
+/// Build a `Schema` out of an object of `{column: dtype}`.
+pub(crate) fn js_to_schema(v: JsValue) -> JsResult<Option<Schema>> {
+    if v.is_null() || v.is_undefined() {
+        return Ok(None);
+    }
+    let obj: js_sys::Object = v.unchecked_into();
+    let fields = js_sys::Object::entries(&obj)
+        .iter()
+        .map(|entry| {
+            let entry: js_sys::Array = entry.unchecked_into();
+            let name = entry.get(0).as_string().unwrap();
+            let dtype = entry.get(1).as_string().ok_or_else(|| {
+                JsPolarsErr::Other(format!("dtype for {} must be a string", name))
+            })?;
+            Ok(Field::new(&name, str_to_polarstype(&dtype)?))
+        })
+        .collect::<Result<Vec<_>, JsPolarsErr>>()?;
+    Ok(Some(Schema::from(fields.into_iter())))
+}
+
+#[wasm_bindgen]
+pub fn read_parquet(
+    buff: JsValue,
+    columns: Option<js_sys::Array>,
+    projection: Option<Vec<u32>>,
+    n_rows: Option<u32>,
+    parallel: &str,
+    row_count_name: Option<String>,
+    row_count_offset: u32,
+    compression: &str,
+) -> JsResult<JsDataFrame> {
+    let parallel = match parallel {
+        "auto" => ParallelStrategy::Auto,
+        "columns" => ParallelStrategy::Columns,
+        "row_groups" => ParallelStrategy::RowGroups,
+        "none" => ParallelStrategy::None,
+        s => {
+            return Err(JsPolarsErr::Other(format!(
+                "parallel should be one of auto, columns, row_groups, none, got {}",
+                s
+            ))
+            .into())
+        }
+    };
+    let columns = columns
+        .map(|cols| js_to_column_names(&cols, "columns"))
+        .transpose()?;
+    let projection = projection.map(|p| p.into_iter().map(|i| i as usize).collect());
+    let n_rows = n_rows.map(|i| i as usize);
+    let row_count = row_count_name.map(|name| RowCount {
+        name,
+        offset: row_count_offset as IdxSize,
+    });
+
+    let buff = InputBytes::from_js(buff)?;
+    let buff = decompress(&buff, compression)?;
+    let cursor = Cursor::new(buff.as_ref());
+    let df = ParquetReader::new(cursor)
+        .with_columns(columns)
+        .with_projection(projection)
+        .with_n_rows(n_rows)
+        .read_parallel(parallel)
+        .with_row_count(row_count)
+        .finish()
+        .map_err(JsPolarsErr::from)?;
+
+    Ok(df.into())
+}
+
+#[wasm_bindgen]
+pub fn read_ipc(
+    buff: JsValue,
+    columns: Option<js_sys::Array>,
+    projection: Option<Vec<u32>>,
+    n_rows: Option<u32>,
+    row_count_name: Option<String>,
+    row_count_offset: u32,
+    compression: &str,
+) -> JsResult<JsDataFrame> {
+    let columns = columns
+        .map(|cols| js_to_column_names(&cols, "columns"))
+        .transpose()?;
+    let projection = projection.map(|p| p.into_iter().map(|i| i as usize).collect());
+    let n_rows = n_rows.map(|i| i as usize);
+    let row_count = row_count_name.map(|name| RowCount {
+        name,
+        offset: row_count_offset as IdxSize,
+    });
+
+    let buff = InputBytes::from_js(buff)?;
+    let buff = decompress(&buff, compression)?;
+    let cursor = Cursor::new(buff.as_ref());
+    let df = IpcReader::new(cursor)
+        .with_columns(columns)
+        .with_projection(projection)
+        .with_n_rows(n_rows)
+        .with_row_count(row_count)
+        .finish()
+        .map_err(JsPolarsErr::from)?;
+
+    Ok(df.into())
+}
+
+pub(crate) fn str_to_ipc_compression(compression: &str) -> JsResult<Option<IpcCompression>> {
+    match compression {
+        "uncompressed" => Ok(None),
+        "lz4" => Ok(Some(IpcCompression::LZ4)),
+        "zstd" => Ok(Some(IpcCompression::ZSTD)),
+        s => Err(JsPolarsErr::Other(format!(
+            "compression should be one of uncompressed, lz4, zstd, got {}",
+            s
+        ))
+        .into()),
+    }
+}
+
+pub(crate) fn str_to_parquet_compression(
+    compression: &str,
+    compression_level: Option<i32>,
+) -> JsResult<ParquetCompression> {
+    let compression = match compression {
+        "uncompressed" => ParquetCompression::Uncompressed,
+        "snappy" => ParquetCompression::Snappy,
+        "lz4" => ParquetCompression::Lz4Raw,
+        "zstd" => ParquetCompression::Zstd(
+            compression_level
+                .map(|lvl| {
+                    ZstdLevel::try_new(lvl).map_err(|e| JsPolarsErr::Other(format!("{:?}", e)))
+                })
+                .transpose()?,
+        ),
+        s => {
+            return Err(JsPolarsErr::Other(format!(
+                "compression should be one of uncompressed, snappy, lz4, zstd, got {}",
+                s
+            ))
+            .into())
+        }
+    };
+    Ok(compression)
+}
+
+pub(crate) fn str_to_quote_style(quote_style: &str) -> JsResult<QuoteStyle> {
+    match quote_style {
+        "necessary" => Ok(QuoteStyle::Necessary),
+        "always" => Ok(QuoteStyle::Always),
+        "non_numeric" => Ok(QuoteStyle::NonNumeric),
+        s => Err(JsPolarsErr::Other(format!(
+            "quote_style should be one of necessary, always, non_numeric, got {}",
+            s
+        ))
+        .into()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn read_json(
+    buff: JsValue,
+    infer_schema_length: Option<u32>,
+    batch_size: u32,
+    schema: JsValue,
+    compression: &str,
+) -> JsResult<JsDataFrame> {
+    let infer_schema_length = infer_schema_length.map(|i| i as usize);
+    let schema = js_to_schema(schema)?;
+
+    let buff = InputBytes::from_js(buff)?;
+    let buff = decompress(&buff, compression)?;
+    let cursor = Cursor::new(buff.as_ref());
+    let mut reader = JsonReader::new(cursor)
+        .with_json_format(JsonFormat::Json)
+        .infer_schema_len(infer_schema_length)
+        .with_batch_size(batch_size as usize);
+    if let Some(schema) = &schema {
+        reader = reader.with_schema(schema);
+    }
+    let df = reader.finish().map_err(JsPolarsErr::from)?;
+
+    Ok(df.into())
+}
+
+#[wasm_bindgen]
+pub fn read_ndjson(
+    buff: JsValue,
+    infer_schema_length: Option<u32>,
+    batch_size: u32,
+    n_rows: Option<u32>,
+    low_memory: bool,
+    rechunk: bool,
+    schema: JsValue,
+    compression: &str,
+) -> JsResult<JsDataFrame> {
+    let infer_schema_length = infer_schema_length.map(|i| i as usize);
+    let n_rows = n_rows.map(|i| i as usize);
+    let schema = js_to_schema(schema)?;
+
+    let buff = InputBytes::from_js(buff)?;
+    let buff = decompress(&buff, compression)?;
+    let cursor = Cursor::new(buff.as_ref());
+    let mut reader = JsonLineReader::new(cursor)
+        .infer_schema_len(infer_schema_length)
+        .with_batch_size(batch_size as usize)
+        .with_n_rows(n_rows)
+        .low_memory(low_memory)
+        .with_rechunk(rechunk);
+    if let Some(schema) = &schema {
+        reader = reader.with_schema(schema);
+    }
+    let df = reader.finish().map_err(JsPolarsErr::from)?;
+
+    Ok(df.into())
+}
+
+#[wasm_bindgen]
+pub fn read_avro(
+    buff: JsValue,
+    columns: Option<js_sys::Array>,
+    projection: Option<Vec<u32>>,
+    n_rows: Option<u32>,
+    compression: &str,
+) -> JsResult<JsDataFrame> {
+    let columns = columns
+        .map(|cols| js_to_column_names(&cols, "columns"))
+        .transpose()?;
+    let projection = projection.map(|p| p.into_iter().map(|i| i as usize).collect());
+    let n_rows = n_rows.map(|i| i as usize);
+
+    let buff = InputBytes::from_js(buff)?;
+    let buff = decompress(&buff, compression)?;
+    let cursor = Cursor::new(buff.as_ref());
+    let df = AvroReader::new(cursor)
+        .with_columns(columns)
+        .with_projection(projection)
+        .with_n_rows(n_rows)
+        .finish()
+        .map_err(JsPolarsErr::from)?;
+
+    Ok(df.into())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_csv_scan_bytes() {
+        let file = Arc::new(b"a,b\n1,2\n".to_vec());
+        let buf = csv_scan_bytes(&file, "uncompressed", &TextEncoding::Utf8).unwrap();
+        assert!(Arc::ptr_eq(&buf, &file));
+
+        let file = Arc::new(b"\xef\xbb\xbfa,b\n1,2\n".to_vec());
+        let buf = csv_scan_bytes(&file, "uncompressed", &TextEncoding::Utf8).unwrap();
+        assert_eq!(buf.as_slice(), b"a,b\n1,2\n");
+    }
+}
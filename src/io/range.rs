@@ -0,0 +1,255 @@
+use crate::{error::JsPolarsErr, JsResult};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    /// A random access byte source implemented in JS, e.g. on top of HTTP range requests.
+    #[wasm_bindgen(
+        typescript_type = "{ size(): number; read(offset: number, length: number): Uint8Array }"
+    )]
+    pub type ByteRangeSource;
+
+    #[wasm_bindgen(method, catch)]
+    fn size(this: &ByteRangeSource) -> Result<f64, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    fn read(
+        this: &ByteRangeSource,
+        offset: f64,
+        length: f64,
+    ) -> Result<js_sys::Uint8Array, JsValue>;
+}
+
+fn io_err(e: impl std::fmt::Debug) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Other, format!("{:?}", e))
+}
+
+fn other_err(msg: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Other, msg)
+}
+
+/// Random access to the bytes of a file, readable only on the thread that created it.
+pub(crate) trait RangeSource {
+    fn size(&self) -> std::io::Result<u64>;
+
+    fn read_range(&self, offset: u64, length: usize) -> std::io::Result<Vec<u8>>;
+}
+
+impl RangeSource for ByteRangeSource {
+    fn size(&self) -> std::io::Result<u64> {
+        ByteRangeSource::size(self)
+            .map(|n| n as u64)
+            .map_err(io_err)
+    }
+
+    fn read_range(&self, offset: u64, length: usize) -> std::io::Result<Vec<u8>> {
+        let bytes = self.read(offset as f64, length as f64).map_err(io_err)?;
+        let n = length.min(bytes.length() as usize);
+        Ok(bytes.subarray(0, n as u32).to_vec())
+    }
+}
+
+thread_local! {
+    /// The sources created on this thread, by id.
+    static SOURCES: RefCell<HashMap<usize, Box<dyn RangeSource>>> = RefCell::new(HashMap::new());
+}
+
+static NEXT_SOURCE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Ids of sources whose last reader was dropped on another thread than the one owning them,
+/// removed by the owner the next time it registers a source.
+static DROPPED_SOURCES: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+/// Read from the source `id` if it is owned by the current thread.
+fn read_local(id: usize, offset: u64, length: usize) -> Option<std::io::Result<Vec<u8>>> {
+    SOURCES.with(|sources| {
+        sources
+            .borrow()
+            .get(&id)
+            .map(|source| source.read_range(offset, length))
+    })
+}
+
+/// A read made on a thread that doesn't own the source, to be answered by its owner.
+pub(crate) struct ReadRequest {
+    id: usize,
+    offset: u64,
+    length: usize,
+    reply: mpsc::SyncSender<std::io::Result<Vec<u8>>>,
+}
+
+impl ReadRequest {
+    /// Read the requested range on the current thread and send it back to the reader.
+    pub(crate) fn answer(self) {
+        let res = read_local(self.id, self.offset, self.length)
+            .unwrap_or_else(|| Err(other_err("the source isn't owned by this thread")));
+        let _ = self.reply.send(res);
+    }
+}
+
+/// A message to the thread owning the sources: either a value it waits for,
+/// or a read it has to answer in the meantime.
+pub(crate) enum Message<T> {
+    Value(T),
+    Read(ReadRequest),
+}
+
+type Forward = Box<dyn Fn(ReadRequest) + Send>;
+
+/// Where reads made on the thread pool go, set while the owner of the sources waits on a query.
+static FORWARD_READS: Mutex<Option<Forward>> = Mutex::new(None);
+
+/// Forwards the reads made on other threads to `tx` until dropped.
+///
+/// The owner of the sources has to answer them with `recv_serving_reads` while the query runs.
+pub(crate) struct ForwardReads {
+    previous: Option<Forward>,
+}
+
+impl ForwardReads {
+    pub(crate) fn new<T: Send + 'static>(tx: mpsc::SyncSender<Message<T>>) -> Self {
+        let forward: Forward = Box::new(move |request| {
+            let _ = tx.send(Message::Read(request));
+        });
+        let previous = FORWARD_READS.lock().unwrap().replace(forward);
+        ForwardReads { previous }
+    }
+}
+
+impl Drop for ForwardReads {
+    fn drop(&mut self) {
+        *FORWARD_READS.lock().unwrap() = self.previous.take();
+    }
+}
+
+/// Wait for the next value sent to `rx`, answering the reads forwarded in the meantime.
+/// `None` once every sender is gone.
+pub(crate) fn recv_serving_reads<T>(rx: &mpsc::Receiver<Message<T>>) -> Option<T> {
+    loop {
+        match rx.recv().ok()? {
+            Message::Value(value) => return Some(value),
+            Message::Read(request) => request.answer(),
+        }
+    }
+}
+
+/// Run `f` on the thread pool, answering the reads it makes of the sources
+/// owned by the current thread until it returns.
+pub(crate) fn run_serving_reads<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+    let (tx, rx) = mpsc::sync_channel(1);
+    let _reads = ForwardReads::new(tx.clone());
+    rayon::spawn(move || {
+        let _ = tx.send(Message::Value(f()));
+    });
+    recv_serving_reads(&rx).expect("the thread pool dropped the task")
+}
+
+struct SourceId(usize);
+
+impl Drop for SourceId {
+    fn drop(&mut self) {
+        let removed = SOURCES
+            .try_with(|sources| sources.borrow_mut().remove(&self.0).is_some())
+            .unwrap_or(false);
+        if !removed {
+            DROPPED_SOURCES.lock().unwrap().push(self.0);
+        }
+    }
+}
+
+/// `Read + Seek` over a `RangeSource`.
+///
+/// The source stays on the thread that created the reader, where it is read directly.
+/// Reads on any other thread are forwarded to it and wait for its answer, so that thread
+/// has to be serving them, see `run_serving_reads`.
+#[derive(Clone)]
+pub(crate) struct RangeReader {
+    source: Arc<SourceId>,
+    len: u64,
+    pos: u64,
+}
+
+impl RangeReader {
+    pub(crate) fn try_new(source: impl RangeSource + 'static) -> std::io::Result<RangeReader> {
+        let len = source.size()?;
+        let id = NEXT_SOURCE_ID.fetch_add(1, Ordering::Relaxed);
+        SOURCES.with(|sources| {
+            let mut sources = sources.borrow_mut();
+            for id in DROPPED_SOURCES.lock().unwrap().drain(..) {
+                sources.remove(&id);
+            }
+            sources.insert(id, Box::new(source));
+        });
+        Ok(RangeReader {
+            source: Arc::new(SourceId(id)),
+            len,
+            pos: 0,
+        })
+    }
+
+    pub(crate) fn from_js(source: ByteRangeSource) -> JsResult<RangeReader> {
+        RangeReader::try_new(source).map_err(|e| JsPolarsErr::Other(e.to_string()).into())
+    }
+
+    fn read_range(&self, offset: u64, length: usize) -> std::io::Result<Vec<u8>> {
+        let id = self.source.0;
+        if let Some(res) = read_local(id, offset, length) {
+            return res;
+        }
+        let (reply, rx) = mpsc::sync_channel(1);
+        {
+            let forward = FORWARD_READS.lock().unwrap();
+            let forward = forward.as_ref().ok_or_else(|| {
+                other_err(
+                    "the source can only be read while the thread that created it runs the query",
+                )
+            })?;
+            forward(ReadRequest {
+                id,
+                offset,
+                length,
+                reply,
+            });
+        }
+        rx.recv()
+            .map_err(|_| other_err("the thread owning the source stopped answering reads"))?
+    }
+}
+
+impl Read for RangeReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = (buf.len() as u64).min(self.len.saturating_sub(self.pos)) as usize;
+        if n == 0 {
+            return Ok(0);
+        }
+        let bytes = self.read_range(self.pos, n)?;
+        let n = n.min(bytes.len());
+        buf[..n].copy_from_slice(&bytes[..n]);
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for RangeReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(offset) => offset as i64,
+            SeekFrom::End(offset) => self.len as i64 + offset,
+            SeekFrom::Current(offset) => self.pos as i64 + offset,
+        };
+        if pos < 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "invalid seek to a negative position",
+            ));
+        }
+        self.pos = pos as u64;
+        Ok(self.pos)
+    }
+}
@@ -0,0 +1,67 @@
+use std::io::Write;
+
+use wasm_bindgen::prelude::*;
+
+/// Number of bytes buffered before they are handed to the JS callback.
+const SINK_CHUNK_BYTES: usize = 1 << 20;
+
+/// Writer that forwards the encoded output to a JS callback as `Uint8Array` chunks,
+/// so the encoded file never has to be held in wasm memory as a whole.
+pub(crate) struct JsCallbackWriter {
+    on_chunk: js_sys::Function,
+    buf: Vec<u8>,
+    error: Option<JsValue>,
+}
+
+impl JsCallbackWriter {
+    pub(crate) fn new(on_chunk: js_sys::Function) -> Self {
+        JsCallbackWriter {
+            on_chunk,
+            buf: Vec::with_capacity(SINK_CHUNK_BYTES),
+            error: None,
+        }
+    }
+
+    /// Hand the remaining bytes to the callback.
+    /// If the callback threw at any point, its exception is returned instead.
+    pub(crate) fn finish(mut self) -> Result<(), JsValue> {
+        let _ = self.emit();
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    fn emit(&mut self) -> std::io::Result<()> {
+        if self.error.is_some() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "sink callback threw an exception",
+            ));
+        }
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let chunk = js_sys::Uint8Array::from(self.buf.as_slice());
+        self.buf.clear();
+        if let Err(e) = self.on_chunk.call1(&JsValue::null(), &chunk) {
+            self.error = Some(e);
+            return self.emit();
+        }
+        Ok(())
+    }
+}
+
+impl Write for JsCallbackWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        if self.buf.len() >= SINK_CHUNK_BYTES {
+            self.emit()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.emit()
+    }
+}
@@ -0,0 +1,290 @@
+use crate::{error::JsPolarsErr, JsResult};
+use polars::prelude::*;
+use std::io::Cursor;
+
+use super::encoding::TextEncoding;
+
+const DELIMITERS: [u8; 5] = [b',', b';', b'\t', b'|', b':'];
+const MAX_LINES: usize = 100;
+
+/// The dialect of a CSV file as detected by `sniff`.
+pub(crate) struct CsvDialect {
+    pub delimiter: u8,
+    pub quote_char: Option<u8>,
+    pub has_header: bool,
+    pub eol_char: u8,
+    pub encoding: &'static str,
+}
+
+/// Guess the encoding from a BOM, or by checking if the sample is valid UTF-8.
+fn sniff_encoding(sample: &[u8], truncated: bool) -> &'static str {
+    if sample.starts_with(&[0xff, 0xfe]) {
+        return "utf-16le";
+    }
+    if sample.starts_with(&[0xfe, 0xff]) {
+        return "utf-16be";
+    }
+    match std::str::from_utf8(sample) {
+        Ok(_) => "utf8",
+        // the sample may end in the middle of a multi-byte character
+        Err(e) if truncated && e.error_len().is_none() => "utf8",
+        Err(_) => "windows-1252",
+    }
+}
+
+fn sniff_eol(text: &[u8]) -> u8 {
+    let newlines = text.iter().filter(|b| **b == b'\n').count();
+    let returns = text.iter().filter(|b| **b == b'\r').count();
+    // `\r\n` line endings are parsed fine with `\n` as end of line character
+    if newlines == 0 && returns > 0 {
+        b'\r'
+    } else {
+        b'\n'
+    }
+}
+
+/// `None` if the sample has no quoted fields.
+fn sniff_quote_char(lines: &[&[u8]]) -> Option<u8> {
+    let count = |q: u8| {
+        lines
+            .iter()
+            .flat_map(|l| l.iter())
+            .filter(|b| **b == q)
+            .count()
+    };
+    if count(b'"') > 0 {
+        Some(b'"')
+    } else if lines.iter().any(|l| l.first() == Some(&b'\'')) && count(b'\'') % 2 == 0 {
+        Some(b'\'')
+    } else {
+        None
+    }
+}
+
+/// Split a line into fields, ignoring delimiters inside quotes.
+fn split_fields(line: &[u8], delimiter: u8, quote_char: Option<u8>) -> Vec<&[u8]> {
+    let mut fields = vec![];
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (i, &b) in line.iter().enumerate() {
+        if Some(b) == quote_char {
+            in_quotes = !in_quotes;
+        } else if b == delimiter && !in_quotes {
+            fields.push(&line[start..i]);
+            start = i + 1;
+        }
+    }
+    fields.push(&line[start..]);
+    fields
+}
+
+/// Pick the delimiter that splits the most lines into the same number of fields.
+fn sniff_delimiter(lines: &[&[u8]], quote_char: Option<u8>) -> u8 {
+    let mut best = (b',', 0, 0);
+    for &delimiter in DELIMITERS.iter() {
+        let counts: Vec<usize> = lines
+            .iter()
+            .map(|l| split_fields(l, delimiter, quote_char).len())
+            .collect();
+        let mode = counts.iter().copied().max_by_key(|c| {
+            let freq = counts.iter().filter(|other| *other == c).count();
+            (freq, *c)
+        });
+        if let Some(mode) = mode {
+            if mode < 2 {
+                continue;
+            }
+            let consistent = counts.iter().filter(|c| **c == mode).count();
+            if (consistent, mode) > (best.1, best.2) {
+                best = (delimiter, consistent, mode);
+            }
+        }
+    }
+    best.0
+}
+
+fn trim(field: &[u8]) -> &[u8] {
+    let start = field
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(field.len());
+    let end = field
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |i| i + 1);
+    &field[start..end]
+}
+
+fn unquote(field: &[u8], quote_char: Option<u8>) -> &[u8] {
+    let field = trim(field);
+    match (quote_char, field.first(), field.last()) {
+        (Some(q), Some(first), Some(last)) if field.len() > 1 && *first == q && *last == q => {
+            &field[1..field.len() - 1]
+        }
+        _ => field,
+    }
+}
+
+fn is_numeric(field: &[u8]) -> bool {
+    std::str::from_utf8(field)
+        .map(|s| s.parse::<f64>().is_ok())
+        .unwrap_or(false)
+}
+
+/// Vote on every column: a text first row above a numeric column, or a first row
+/// that differs in length from a column of fixed length values, indicates a header.
+fn sniff_header(lines: &[&[u8]], delimiter: u8, quote_char: Option<u8>) -> bool {
+    let rows: Vec<Vec<&[u8]>> = lines
+        .iter()
+        .map(|l| {
+            split_fields(l, delimiter, quote_char)
+                .into_iter()
+                .map(|f| unquote(f, quote_char))
+                .collect()
+        })
+        .collect();
+    let (header, body) = match rows.split_first() {
+        Some((header, body)) if !body.is_empty() => (header, body),
+        _ => return true,
+    };
+
+    let mut votes = 0i32;
+    for (idx, name) in header.iter().enumerate() {
+        let values: Vec<&[u8]> = body
+            .iter()
+            .filter_map(|row| row.get(idx).copied())
+            .filter(|v| !v.is_empty())
+            .collect();
+        if values.is_empty() {
+            continue;
+        }
+        if values.iter().all(|v| is_numeric(v)) {
+            votes += if is_numeric(name) { -1 } else { 1 };
+        } else {
+            let len = values[0].len();
+            if values.iter().all(|v| v.len() == len) {
+                votes += if name.len() == len { -1 } else { 1 };
+            }
+        }
+    }
+    votes >= 0
+}
+
+/// Detect the dialect of a CSV file from the first `sample_bytes` bytes.
+pub(crate) fn sniff(buf: &[u8], sample_bytes: usize) -> JsResult<CsvDialect> {
+    let truncated = buf.len() > sample_bytes;
+    let sample = &buf[..buf.len().min(sample_bytes)];
+    let encoding = sniff_encoding(sample, truncated);
+    let text = TextEncoding::from_option(encoding)?.decode(sample);
+
+    let eol_char = sniff_eol(&text);
+    let mut lines: Vec<&[u8]> = text
+        .split(|b| *b == eol_char)
+        .map(|l| l.strip_suffix(b"\r").unwrap_or(l))
+        .collect();
+    // the last line of a truncated sample is likely incomplete
+    if truncated && lines.len() > 1 {
+        lines.pop();
+    }
+    let lines: Vec<&[u8]> = lines
+        .into_iter()
+        .filter(|l| !trim(l).is_empty())
+        .take(MAX_LINES)
+        .collect();
+    if lines.is_empty() {
+        return Err(JsPolarsErr::Other("cannot sniff the dialect of an empty file".into()).into());
+    }
+
+    let quote_char = sniff_quote_char(&lines);
+    let delimiter = sniff_delimiter(&lines, quote_char);
+    let has_header = sniff_header(&lines, delimiter, quote_char);
+
+    Ok(CsvDialect {
+        delimiter,
+        quote_char,
+        has_header,
+        eol_char,
+        encoding,
+    })
+}
+
+/// Infer the schema of the sampled lines with the detected dialect.
+pub(crate) fn sniff_schema(
+    buf: &[u8],
+    sample_bytes: usize,
+    dialect: &CsvDialect,
+) -> JsResult<Schema> {
+    let sample = &buf[..buf.len().min(sample_bytes)];
+    let text = TextEncoding::from_option(dialect.encoding)?.decode(sample);
+    let end = if buf.len() > sample_bytes {
+        text.iter()
+            .rposition(|b| *b == dialect.eol_char)
+            .map(|i| i + 1)
+            .unwrap_or(text.len())
+    } else {
+        text.len()
+    };
+
+    let df = CsvReader::new(Cursor::new(&text[..end]))
+        .has_header(dialect.has_header)
+        .with_delimiter(dialect.delimiter)
+        .with_quote_char(dialect.quote_char)
+        .with_end_of_line_char(dialect.eol_char)
+        .with_ignore_parser_errors(true)
+        .finish()
+        .map_err(JsPolarsErr::from)?;
+    Ok(df.schema())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sniff_all(text: &[u8]) -> CsvDialect {
+        sniff(text, 64 * 1024).unwrap()
+    }
+
+    #[test]
+    fn test_sniff_semicolon() {
+        let dialect = sniff_all(b"a;b;c\n1;2;3\n4;5;6\n");
+        assert_eq!(dialect.delimiter, b';');
+        assert_eq!(dialect.quote_char, None);
+        assert!(dialect.has_header);
+    }
+
+    #[test]
+    fn test_sniff_tab() {
+        let dialect = sniff_all(b"name\tvalue\nx\t1\ny\t2\n");
+        assert_eq!(dialect.delimiter, b'\t');
+        assert!(dialect.has_header);
+    }
+
+    #[test]
+    fn test_sniff_headerless() {
+        let dialect = sniff_all(b"1,2,3\n4,5,6\n7,8,9\n");
+        assert_eq!(dialect.delimiter, b',');
+        assert!(!dialect.has_header);
+    }
+
+    #[test]
+    fn test_sniff_crlf() {
+        let dialect = sniff_all(b"a,b\r\n1,2\r\n3,4\r\n");
+        assert_eq!(dialect.delimiter, b',');
+        assert_eq!(dialect.eol_char, b'\n');
+        assert!(dialect.has_header);
+    }
+
+    #[test]
+    fn test_sniff_latin1() {
+        let dialect = sniff_all(b"name;city\nJos\xe9;M\xfcnchen\nAna;Z\xfcrich\n");
+        assert_eq!(dialect.encoding, "windows-1252");
+        assert_eq!(dialect.delimiter, b';');
+    }
+
+    #[test]
+    fn test_sniff_quoted() {
+        let dialect = sniff_all(b"a,b\n\"x, y\",1\n\"z\",2\n");
+        assert_eq!(dialect.quote_char, Some(b'"'));
+        assert_eq!(dialect.delimiter, b',');
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{error::JsPolarsErr, JsResult};
+use polars::prelude::*;
+use std::collections::BTreeMap;
+use std::ops::Deref;
+use std::sync::RwLock;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+/// In-memory files registered from JS, keyed by path.
+///
+/// Only accessed from the worker and the thread pool: the main thread can't block,
+/// and waiting for a contended lock would trap there. Locks are only held to look up
+/// or clone an `Arc`, the files are never copied while holding them.
+static FILES: RwLock<BTreeMap<String, Arc<Vec<u8>>>> = RwLock::new(BTreeMap::new());
+
+fn normalize(path: &str) -> &str {
+    path.trim_start_matches("./").trim_start_matches('/')
+}
+
+fn is_glob(path: &str) -> bool {
+    path.contains(|c| matches!(c, '*' | '?' | '['))
+}
+
+/// Register a file in the in-memory filesystem, replacing any file at the same path.
+/// Registered paths can be passed to `scan_csv` and the `read_*` functions, and used in globs.
+/// Like the readers, this runs in the worker.
+/// @example
+/// ```js
+/// > await pl.register_file("data/year=2021/part-0.csv", bytes2021)
+/// > await pl.register_file("data/year=2022/part-0.csv", bytes2022)
+/// > (await pl.scan_csv("data/year=*/part-*.csv")).filter(pl.col("year").gt(2021))
+/// ```
+#[wasm_bindgen(js_name = "__register_file_from_worker", skip_typescript)]
+pub fn register_file(path: &str, bytes: Vec<u8>) {
+    FILES
+        .write()
+        .unwrap()
+        .insert(normalize(path).to_string(), Arc::new(bytes));
+}
+
+/// Remove a file from the in-memory filesystem.
+/// @returns whether a file was registered at `path`
+#[wasm_bindgen(js_name = "__unregister_file_from_worker", skip_typescript)]
+pub fn unregister_file(path: &str) -> bool {
+    FILES.write().unwrap().remove(normalize(path)).is_some()
+}
+
+/// List the paths of the registered files matching `pattern`, in sorted order.
+/// @param pattern - a glob such as `data/**/*.csv`, all files are listed if omitted
+#[wasm_bindgen(js_name = "__list_files_from_worker", skip_typescript)]
+pub fn list_files(pattern: Option<String>) -> JsResult<js_sys::Array> {
+    let paths = match pattern {
+        Some(pattern) => resolve_files(&pattern)?
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect(),
+        None => FILES.read().unwrap().keys().cloned().collect::<Vec<_>>(),
+    };
+    Ok(paths.into_iter().map(JsValue::from).collect())
+}
+
+/// The registered files matching `pattern`, which is either a path or a glob.
+pub(crate) fn resolve_files(pattern: &str) -> JsResult<Vec<(String, Arc<Vec<u8>>)>> {
+    let pattern = normalize(pattern);
+    let files = FILES.read().unwrap();
+    let matches: Vec<_> = if is_glob(pattern) {
+        let glob = glob::Pattern::new(pattern)
+            .map_err(|e| JsPolarsErr::Other(format!("invalid glob '{}': {}", pattern, e)))?;
+        // `*` stays within a directory, `**` crosses directories
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        files
+            .iter()
+            .filter(|(path, _)| glob.matches_with(path, options))
+            .map(|(path, buf)| (path.clone(), buf.clone()))
+            .collect()
+    } else {
+        files
+            .get_key_value(pattern)
+            .map(|(path, buf)| (path.clone(), buf.clone()))
+            .into_iter()
+            .collect()
+    };
+    Ok(matches)
+}
+
+/// The bytes passed to a reader, either a `Uint8Array` copied into wasm memory
+/// or the path of a registered file, which is read in place.
+pub(crate) enum InputBytes {
+    Copied(Vec<u8>),
+    Registered(Arc<Vec<u8>>),
+}
+
+impl InputBytes {
+    pub(crate) fn from_js(buff: JsValue) -> JsResult<Self> {
+        if let Some(path) = buff.as_string() {
+            let file = FILES.read().unwrap().get(normalize(&path)).cloned();
+            return match file {
+                Some(buf) => Ok(InputBytes::Registered(buf)),
+                None => Err(JsPolarsErr::Other(format!("no registered file at '{}'", path)).into()),
+            };
+        }
+        let buff = buff.dyn_into::<js_sys::Uint8Array>().map_err(|_| {
+            JsPolarsErr::Other("expected a Uint8Array or the path of a registered file".into())
+        })?;
+        Ok(InputBytes::Copied(buff.to_vec()))
+    }
+}
+
+impl Deref for InputBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            InputBytes::Copied(buf) => buf,
+            InputBytes::Registered(buf) => buf,
+        }
+    }
+}
+
+/// Hive-style `key=value` partitions in the directories of `path`.
+pub(crate) fn hive_partitions(path: &str) -> Vec<(&str, &str)> {
+    let mut segments: Vec<&str> = path.split('/').collect();
+    // the file name itself is not a partition
+    segments.pop();
+    segments
+        .into_iter()
+        .filter_map(|segment| segment.split_once('='))
+        .collect()
+}
+
+/// The dtype of a partition column: `Int64` or `Float64` if all its values parse as such,
+/// `Utf8` otherwise.
+pub(crate) fn partition_dtype<'a>(values: impl Iterator<Item = &'a str> + Clone) -> DataType {
+    if values.clone().all(|v| v.parse::<i64>().is_ok()) {
+        DataType::Int64
+    } else if values.clone().all(|v| v.parse::<f64>().is_ok()) {
+        DataType::Float64
+    } else {
+        DataType::Utf8
+    }
+}
+
+/// A literal expression of a partition value in `dtype`, as inferred by `partition_dtype`.
+pub(crate) fn partition_lit(value: &str, dtype: &DataType) -> Expr {
+    match dtype {
+        DataType::Int64 => lit(value.parse::<i64>().unwrap()),
+        DataType::Float64 => lit(value.parse::<f64>().unwrap()),
+        _ => lit(value),
+    }
+}
@@ -1,7 +1,19 @@
+use polars::export::arrow::io::ipc::write::{
+    FileWriter as IpcFileWriter, WriteOptions as IpcWriteOptions,
+};
+use polars::export::arrow::io::parquet::write::{
+    transverse, Encoding, FileWriter as ParquetFileWriter, RowGroupIterator, Version,
+    WriteOptions as ParquetWriteOptions,
+};
 use polars::prelude::*;
+use std::sync::{mpsc, Mutex};
 use wasm_bindgen::prelude::*;
 
 use super::expr::JsExpr;
+use crate::io::{
+    recv_serving_reads, run_serving_reads, str_to_byte, str_to_ipc_compression,
+    str_to_parquet_compression, str_to_quote_style, ForwardReads, JsCallbackWriter, Message,
+};
 use crate::{dataframe::JsDataFrame, error::JsPolarsErr, JsResult};
 #[wasm_bindgen(js_name = LazyFrame)]
 #[repr(transparent)]
@@ -22,6 +34,82 @@ impl JsLazyFrame {
     }
 }
 
+/// Number of batches the query may run ahead of the sink consuming them.
+const SINK_QUEUE_BATCHES: usize = 2;
+
+/// The result of a query, batch by batch, in the order the engine produces the batches.
+///
+/// The query runs on the thread pool with the streaming engine and hands every batch over
+/// as soon as it is produced, blocking while `SINK_QUEUE_BATCHES` batches wait to be consumed,
+/// so the result is never held as a whole. Plans the streaming engine doesn't support
+/// produce their result as a single batch.
+///
+/// Reads the query makes of byte range sources owned by this thread are answered
+/// while waiting for the next batch.
+struct Batches {
+    rx: mpsc::Receiver<Message<PolarsResult<Option<DataFrame>>>>,
+    _reads: ForwardReads,
+    done: bool,
+}
+
+impl Batches {
+    fn new(ldf: LazyFrame) -> Self {
+        let (tx, rx) = mpsc::sync_channel(SINK_QUEUE_BATCHES);
+        let reads = ForwardReads::new(tx.clone());
+        let sender = Mutex::new(tx.clone());
+        let options = AllowedOptimizations {
+            streaming: true,
+            ..Default::default()
+        };
+        let ldf = ldf
+            .map(
+                move |batch| {
+                    let empty = batch.slice(0, 0);
+                    sender
+                        .lock()
+                        .unwrap()
+                        .send(Message::Value(Ok(Some(batch))))
+                        .map_err(|_| PolarsError::ComputeError("sink was closed".into()))?;
+                    Ok(empty)
+                },
+                Some(options),
+                None,
+                Some("SINK"),
+            )
+            .with_streaming(true);
+        rayon::spawn(move || {
+            // `None` marks the end, the batches were all sent by the time `collect` returns
+            let _ = tx.send(Message::Value(ldf.collect().map(|_| None)));
+        });
+        Batches {
+            rx,
+            _reads: reads,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for Batches {
+    type Item = PolarsResult<DataFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match recv_serving_reads(&self.rx) {
+            Some(Ok(Some(batch))) => Some(Ok(batch)),
+            Some(Err(e)) => {
+                self.done = true;
+                Some(Err(e))
+            }
+            Some(Ok(None)) | None => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
 #[wasm_bindgen(js_class=LazyFrame)]
 impl JsLazyFrame {
     #[wasm_bindgen(getter)]
@@ -40,9 +128,8 @@ impl JsLazyFrame {
     }
     #[wasm_bindgen(js_name = "__collect_from_worker", skip_typescript)]
     pub fn collect_from_worker(&self) -> JsResult<JsDataFrame> {
-        self.ldf
-            .clone()
-            .collect()
+        let ldf = self.ldf.clone();
+        run_serving_reads(move || ldf.collect())
             .map_err(|e| JsPolarsErr::from(e).into())
             .map(|df| df.into())
     }
@@ -198,22 +285,158 @@ impl JsLazyFrame {
         let ldf = self.ldf.clone();
         Ok(ldf.select(&exprs).into())
     }
+
+    /// Run the query and stream its result as CSV to `on_chunk`,
+    /// which is called with every encoded `Uint8Array` chunk.
+    /// The result is encoded batch by batch as the query produces it,
+    /// so rows may not be in the order `collect` would return them.
+    /// Like `collect`, this runs in the worker.
+    #[wasm_bindgen(js_name = "__sink_csv_from_worker", skip_typescript)]
+    pub fn sink_csv(
+        &self,
+        on_chunk: js_sys::Function,
+        has_header: bool,
+        sep: String,
+        quote: String,
+        quote_style: &str,
+        null_value: String,
+        batch_size: usize,
+    ) -> JsResult<()> {
+        let sep = str_to_byte(&sep, "sep")?;
+        let quote = str_to_byte(&quote, "quote")?;
+        let quote_style = str_to_quote_style(quote_style)?;
+        let schema = self.get_schema()?;
+        let write = |writer: &mut JsCallbackWriter, df: &mut DataFrame, has_header: bool| {
+            CsvWriter::new(writer)
+                .has_header(has_header)
+                .with_delimiter(sep)
+                .with_quoting_char(quote)
+                .with_quote_style(quote_style)
+                .with_null_value(null_value.clone())
+                .with_batch_size(batch_size)
+                .finish(df)
+        };
+
+        let mut writer = JsCallbackWriter::new(on_chunk);
+        let res = (|| -> PolarsResult<()> {
+            // only the first batch gets the header
+            let mut has_header = has_header;
+            for batch in Batches::new(self.ldf.clone()) {
+                write(&mut writer, &mut batch?, has_header)?;
+                has_header = false;
+            }
+            if has_header {
+                write(&mut writer, &mut DataFrame::from(schema.as_ref()), true)?;
+            }
+            Ok(())
+        })();
+        writer.finish()?;
+        res.map_err(JsPolarsErr::from)?;
+        Ok(())
+    }
+
+    /// Run the query and stream its result as Parquet to `on_chunk`,
+    /// which is called with every encoded `Uint8Array` chunk.
+    /// The result is encoded batch by batch as the query produces it,
+    /// so rows may not be in the order `collect` would return them,
+    /// and row groups never span two batches.
+    /// Like `collect`, this runs in the worker.
+    /// @param compression - one of `uncompressed`, `snappy`, `lz4` or `zstd`
+    /// @param compression_level - only used by `zstd`
+    #[wasm_bindgen(js_name = "__sink_parquet_from_worker", skip_typescript)]
+    pub fn sink_parquet(
+        &self,
+        on_chunk: js_sys::Function,
+        compression: &str,
+        compression_level: Option<i32>,
+        statistics: bool,
+        row_group_size: Option<usize>,
+    ) -> JsResult<()> {
+        let compression = str_to_parquet_compression(compression, compression_level)?;
+        let schema = self.get_schema()?.to_arrow();
+        let options = ParquetWriteOptions {
+            write_statistics: statistics,
+            compression,
+            version: Version::V2,
+        };
+        let encodings: Vec<_> = schema
+            .fields
+            .iter()
+            .map(|f| transverse(&f.data_type, |_| Encoding::Plain))
+            .collect();
+
+        let mut writer = JsCallbackWriter::new(on_chunk);
+        let res = (|| -> PolarsResult<()> {
+            let mut file = ParquetFileWriter::try_new(&mut writer, schema.clone(), options)?;
+            for batch in Batches::new(self.ldf.clone()) {
+                let mut batch = batch?;
+                batch.rechunk();
+                let size = row_group_size.unwrap_or(batch.height()).max(1);
+                for offset in (0..batch.height()).step_by(size) {
+                    let chunks = batch.slice(offset as i64, size).iter_chunks().map(Ok);
+                    let row_groups =
+                        RowGroupIterator::try_new(chunks, &schema, options, encodings.clone())?;
+                    for group in row_groups {
+                        file.write(group?)?;
+                    }
+                }
+            }
+            file.end(None)?;
+            Ok(())
+        })();
+        writer.finish()?;
+        res.map_err(JsPolarsErr::from)?;
+        Ok(())
+    }
+
+    /// Run the query and stream its result as Arrow IPC to `on_chunk`,
+    /// which is called with every encoded `Uint8Array` chunk.
+    /// The result is encoded batch by batch as the query produces it,
+    /// so rows may not be in the order `collect` would return them.
+    /// Like `collect`, this runs in the worker.
+    /// @param compression - one of `uncompressed`, `lz4` or `zstd`
+    #[wasm_bindgen(js_name = "__sink_ipc_from_worker", skip_typescript)]
+    pub fn sink_ipc(&self, on_chunk: js_sys::Function, compression: &str) -> JsResult<()> {
+        let compression = str_to_ipc_compression(compression)?;
+        let schema = self.get_schema()?.to_arrow();
+        let options = IpcWriteOptions {
+            compression: compression.map(|c| c.into()),
+        };
+
+        let mut writer = JsCallbackWriter::new(on_chunk);
+        let res = (|| -> PolarsResult<()> {
+            let mut file = IpcFileWriter::try_new(&mut writer, &schema, None, options)?;
+            for batch in Batches::new(self.ldf.clone()) {
+                let mut batch = batch?;
+                batch.rechunk();
+                for chunk in batch.iter_chunks() {
+                    file.write(&chunk, None)?;
+                }
+            }
+            file.finish()?;
+            Ok(())
+        })();
+        writer.finish()?;
+        res.map_err(JsPolarsErr::from)?;
+        Ok(())
+    }
 }
 
 pub(crate) fn js_exprs_to_exprs(iter: &js_sys::Array) -> JsResult<Box<[Expr]>> {
-    use wasm_bindgen::convert::FromWasmAbi;
-    use wasm_bindgen::JsCast;
     let iterator = js_sys::try_iter(iter)?.ok_or_else(|| "need to pass iterable JS values!")?;
 
     iterator
         .into_iter()
-        .map(|jsv| {
-            let jsv = jsv?;
-            let key = JsValue::from_str("ptr");
-            let ptr = js_sys::Reflect::get(&jsv, &key)?;
-            let n: f64 = js_sys::Number::unchecked_from_js(ptr).into();
-            let expr: JsExpr = unsafe { JsExpr::from_abi(n as u32) };
-            Ok(expr.inner)
-        })
+        .map(|jsv| js_expr_to_expr(&jsv?))
         .collect()
 }
+
+pub(crate) fn js_expr_to_expr(jsv: &JsValue) -> JsResult<Expr> {
+    use wasm_bindgen::convert::FromWasmAbi;
+    use wasm_bindgen::JsCast;
+    let key = JsValue::from_str("ptr");
+    let ptr = js_sys::Reflect::get(jsv, &key)?;
+    let n: f64 = js_sys::Number::unchecked_from_js(ptr).into();
+    let expr: JsExpr = unsafe { JsExpr::from_abi(n as u32) };
+    Ok(expr.inner)
+}
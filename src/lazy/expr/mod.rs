@@ -45,7 +45,7 @@ impl JsExpr {
     /// ...  ],
     /// ...  "value": [94, 95, 96, 97, 97, 99],
     /// ... })
-    /// >>> df.groupby("group", {maintain_order:True}).agg(pl.col("value").agg_groups())
+    /// > df.groupby(["group"], true).agg([pl.col("value").agg_groups()])
     /// shape: (2, 2)
     /// ┌───────┬───────────┐
     /// │ group ┆ value     │
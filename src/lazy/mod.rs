@@ -1,3 +1,4 @@
 pub mod dataframe;
 pub mod expr;
 pub mod lazy_functions;
+pub(crate) mod scan;
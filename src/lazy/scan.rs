@@ -0,0 +1,541 @@
+use crate::io::RangeReader;
+use polars::export::arrow::datatypes::{Field as ArrowField, Schema as ArrowSchema};
+use polars::export::arrow::io::parquet::read::{self, statistics, FileMetaData};
+use polars::io::csv::utils::infer_file_schema;
+use polars::io::mmap::ReaderBytes;
+use polars::prelude::*;
+use std::io::Cursor;
+use std::ops::Range;
+
+/// Parse options of a CSV scan over an in-memory buffer.
+#[derive(Clone)]
+pub(crate) struct CsvScanOptions {
+    pub has_header: bool,
+    pub ignore_errors: bool,
+    pub skip_rows: usize,
+    pub parse_dates: bool,
+    pub delimiter: u8,
+    pub quote_char: Option<u8>,
+    pub comment_char: Option<u8>,
+    pub eol_char: u8,
+    pub null_values: Option<NullValues>,
+    pub overwrite_dtype: Option<Schema>,
+    pub encoding: CsvEncoding,
+}
+
+/// Bytes of CSV parsed at a time by a scan with a predicate.
+const CSV_SCAN_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+/// Ranges of whole lines of at least `chunk_bytes` bytes, skipping the first `skip_lines`
+/// lines. Line terminators inside quoted fields don't end lines.
+fn line_chunks(
+    buf: &[u8],
+    quote_char: Option<u8>,
+    eol_char: u8,
+    skip_lines: usize,
+    chunk_bytes: usize,
+) -> Vec<Range<usize>> {
+    let mut chunks = vec![];
+    let mut in_quotes = false;
+    let mut skipped = 0;
+    let mut start = 0;
+    for (i, &b) in buf.iter().enumerate() {
+        if Some(b) == quote_char {
+            in_quotes = !in_quotes;
+        } else if b == eol_char && !in_quotes {
+            if skipped < skip_lines {
+                skipped += 1;
+                start = i + 1;
+            } else if i + 1 - start >= chunk_bytes {
+                chunks.push(start..i + 1);
+                start = i + 1;
+            }
+        }
+    }
+    if skipped == skip_lines && start < buf.len() {
+        chunks.push(start..buf.len());
+    }
+    chunks
+}
+
+/// A CSV source that parses its bytes only when the query is executed,
+/// reading just the projected columns.
+///
+/// With a predicate, the bytes are parsed in chunks of `CSV_SCAN_CHUNK_BYTES`
+/// and every chunk is filtered as soon as it is parsed, so the rows that don't
+/// match are dropped before the next chunk is read.
+pub(crate) struct CsvBytesScan {
+    buf: Arc<Vec<u8>>,
+    options: CsvScanOptions,
+}
+
+impl CsvBytesScan {
+    pub(crate) fn new(buf: impl Into<Arc<Vec<u8>>>, options: CsvScanOptions) -> Self {
+        CsvBytesScan {
+            buf: buf.into(),
+            options,
+        }
+    }
+
+    fn reader(&self) -> CsvReader<Cursor<&[u8]>> {
+        let options = &self.options;
+        CsvReader::new(Cursor::new(self.buf.as_slice()))
+            .has_header(options.has_header)
+            .with_ignore_parser_errors(options.ignore_errors)
+            .with_skip_rows(options.skip_rows)
+            .with_parse_dates(options.parse_dates)
+            .with_delimiter(options.delimiter)
+            .with_quote_char(options.quote_char)
+            .with_comment_char(options.comment_char)
+            .with_end_of_line_char(options.eol_char)
+            .with_null_values(options.null_values.clone())
+            .with_encoding(options.encoding)
+    }
+}
+
+impl AnonymousScan for CsvBytesScan {
+    fn scan(&self, scan_opts: AnonymousScanOptions) -> PolarsResult<DataFrame> {
+        let predicate = match scan_opts.predicate {
+            Some(predicate) => predicate,
+            None => {
+                return self
+                    .reader()
+                    .with_schema(scan_opts.schema.clone())
+                    .with_columns(scan_opts.with_columns.map(|cols| cols.as_ref().clone()))
+                    .with_n_rows(scan_opts.n_rows)
+                    .finish();
+            }
+        };
+
+        // the columns the predicate needs are parsed too and selected away after filtering
+        let mut columns: Vec<String> = match &scan_opts.with_columns {
+            Some(cols) => cols.as_ref().clone(),
+            None => scan_opts.schema.iter_names().cloned().collect(),
+        };
+        let n_projected = columns.len();
+        for name in expr_to_root_column_names(&predicate) {
+            if !columns.iter().any(|c| c.as_str() == name.as_ref()) {
+                columns.push(name.to_string());
+            }
+        }
+
+        let options = &self.options;
+        let skip_lines = options.skip_rows + options.has_header as usize;
+        let chunks = line_chunks(
+            &self.buf,
+            options.quote_char,
+            options.eol_char,
+            skip_lines,
+            CSV_SCAN_CHUNK_BYTES,
+        );
+        let mut remaining = scan_opts.n_rows;
+        let mut dfs = vec![];
+        for chunk in chunks {
+            if remaining == Some(0) {
+                break;
+            }
+            let df = CsvReader::new(Cursor::new(&self.buf[chunk]))
+                .has_header(false)
+                .with_ignore_parser_errors(options.ignore_errors)
+                .with_parse_dates(options.parse_dates)
+                .with_delimiter(options.delimiter)
+                .with_quote_char(options.quote_char)
+                .with_comment_char(options.comment_char)
+                .with_end_of_line_char(options.eol_char)
+                .with_null_values(options.null_values.clone())
+                .with_encoding(options.encoding)
+                .with_schema(scan_opts.schema.clone())
+                .with_columns(Some(columns.clone()))
+                .finish()?;
+            let mut df = df.lazy().filter(predicate.clone()).collect()?;
+            if let Some(n) = remaining {
+                df = df.head(Some(n));
+                remaining = Some(n - df.height());
+            }
+            dfs.push(df);
+        }
+
+        let df = match dfs.is_empty() {
+            true => DataFrame::new_no_checks(
+                columns
+                    .iter()
+                    .map(|name| {
+                        let dtype = scan_opts.schema.try_get(name)?;
+                        Ok(Series::new_empty(name, dtype))
+                    })
+                    .collect::<PolarsResult<_>>()?,
+            ),
+            false => accumulate_dataframes_vertical(dfs)?,
+        };
+        df.select(&columns[..n_projected])
+    }
+
+    /// Infer the dtypes from the text of the first `infer_schema_length` lines,
+    /// without building a DataFrame out of them.
+    fn schema(&self, infer_schema_length: Option<usize>) -> PolarsResult<Schema> {
+        let options = &self.options;
+        let mut skip_rows = options.skip_rows;
+        let (schema, _) = infer_file_schema(
+            &ReaderBytes::Borrowed(self.buf.as_slice()),
+            options.delimiter,
+            infer_schema_length,
+            options.has_header,
+            options.overwrite_dtype.as_ref(),
+            &mut skip_rows,
+            0,
+            options.comment_char,
+            options.quote_char,
+            options.eol_char,
+            options.null_values.as_ref(),
+            options.parse_dates,
+        )?;
+        Ok(schema)
+    }
+
+    fn allows_predicate_pushdown(&self) -> bool {
+        true
+    }
+
+    fn allows_projection_pushdown(&self) -> bool {
+        true
+    }
+
+    fn allows_slice_pushdown(&self) -> bool {
+        true
+    }
+}
+
+/// A Parquet file read through byte range requests.
+///
+/// Only the footer is fetched up front. On execution, row groups whose statistics
+/// can't satisfy the predicate are skipped and only projected columns are fetched.
+pub(crate) struct ParquetRangeScan {
+    reader: RangeReader,
+    metadata: FileMetaData,
+    schema: ArrowSchema,
+}
+
+impl ParquetRangeScan {
+    pub(crate) fn try_new(mut reader: RangeReader) -> PolarsResult<Self> {
+        let metadata = read::read_metadata(&mut reader)?;
+        let schema = read::infer_schema(&metadata)?;
+        Ok(ParquetRangeScan {
+            reader,
+            metadata,
+            schema,
+        })
+    }
+
+    pub(crate) fn polars_schema(&self) -> Schema {
+        Schema::from(self.schema.fields.iter().map(Field::from))
+    }
+
+    fn field(&self, name: &str) -> PolarsResult<&ArrowField> {
+        self.schema
+            .fields
+            .iter()
+            .find(|f| f.name == name)
+            .ok_or_else(|| PolarsError::NotFound(name.to_string().into()))
+    }
+
+    /// Which row groups may hold rows matching `expr`, `None` if the statistics can't tell.
+    fn row_group_mask(&self, expr: &Expr) -> PolarsResult<Option<BooleanChunked>> {
+        match expr {
+            Expr::BinaryExpr {
+                left,
+                op: Operator::And,
+                right,
+            } => Ok(
+                match (self.row_group_mask(left)?, self.row_group_mask(right)?) {
+                    (Some(l), Some(r)) => Some(&l & &r),
+                    (Some(mask), None) | (None, Some(mask)) => Some(mask),
+                    (None, None) => None,
+                },
+            ),
+            Expr::BinaryExpr {
+                left,
+                op: Operator::Or,
+                right,
+            } => Ok(
+                match (self.row_group_mask(left)?, self.row_group_mask(right)?) {
+                    (Some(l), Some(r)) => Some(&l | &r),
+                    _ => None,
+                },
+            ),
+            Expr::BinaryExpr { left, op, right } => match (left.as_ref(), right.as_ref()) {
+                (Expr::Column(name), Expr::Literal(value)) => self.compare_stats(name, *op, value),
+                (Expr::Literal(value), Expr::Column(name)) => {
+                    let op = match op {
+                        Operator::Gt => Operator::Lt,
+                        Operator::GtEq => Operator::LtEq,
+                        Operator::Lt => Operator::Gt,
+                        Operator::LtEq => Operator::GtEq,
+                        op => *op,
+                    };
+                    self.compare_stats(name, op, value)
+                }
+                _ => Ok(None),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    fn compare_stats(
+        &self,
+        name: &str,
+        op: Operator,
+        value: &LiteralValue,
+    ) -> PolarsResult<Option<BooleanChunked>> {
+        // the statistics only let row groups be skipped, when they can't be
+        // compared with `value` every row group is read
+        Ok(self.try_compare_stats(name, op, value).unwrap_or(None))
+    }
+
+    fn try_compare_stats(
+        &self,
+        name: &str,
+        op: Operator,
+        value: &LiteralValue,
+    ) -> PolarsResult<Option<BooleanChunked>> {
+        if !matches!(
+            op,
+            Operator::Eq | Operator::Gt | Operator::GtEq | Operator::Lt | Operator::LtEq
+        ) {
+            return Ok(None);
+        }
+        let stats = statistics::deserialize(self.field(name)?, &self.metadata.row_groups)?;
+        let min = Series::try_from(("min", stats.min_value))?;
+        let max = Series::try_from(("max", stats.max_value))?;
+        let value = DataFrame::new_no_checks(vec![])
+            .lazy()
+            .select([Expr::Literal(value.clone())])
+            .collect()?
+            .get_columns()[0]
+            .cast(min.dtype())?;
+
+        let mask = match op {
+            Operator::Eq => &min.lt_eq(&value)? & &max.gt_eq(&value)?,
+            Operator::Gt => max.gt(&value)?,
+            Operator::GtEq => max.gt_eq(&value)?,
+            Operator::Lt => min.lt(&value)?,
+            Operator::LtEq => min.lt_eq(&value)?,
+            _ => unreachable!(),
+        };
+        // row groups without statistics have to be read
+        Ok(Some(mask.fill_null_with_values(true)?))
+    }
+}
+
+impl AnonymousScan for ParquetRangeScan {
+    fn scan(&self, scan_opts: AnonymousScanOptions) -> PolarsResult<DataFrame> {
+        let mut columns: Vec<String> = match &scan_opts.with_columns {
+            Some(cols) => cols.as_ref().clone(),
+            None => self.schema.fields.iter().map(|f| f.name.clone()).collect(),
+        };
+        let n_projected = columns.len();
+        if let Some(predicate) = &scan_opts.predicate {
+            for name in expr_to_root_column_names(predicate) {
+                if !columns.iter().any(|c| c.as_str() == name.as_ref()) {
+                    columns.push(name.to_string());
+                }
+            }
+        }
+        let fields = columns
+            .iter()
+            .map(|name| self.field(name).cloned())
+            .collect::<PolarsResult<Vec<_>>>()?;
+
+        let mask = match &scan_opts.predicate {
+            Some(predicate) => self.row_group_mask(predicate)?,
+            None => None,
+        };
+
+        let mut reader = self.reader.clone();
+        let mut remaining = scan_opts.n_rows;
+        let mut dfs = vec![];
+        for (i, row_group) in self.metadata.row_groups.iter().enumerate() {
+            if remaining == Some(0) {
+                break;
+            }
+            if let Some(mask) = &mask {
+                if mask.get(i) == Some(false) {
+                    continue;
+                }
+            }
+            let column_chunks = read::read_columns_many(
+                &mut reader,
+                row_group,
+                fields.clone(),
+                None,
+                remaining,
+                None,
+            )?;
+            for chunk in
+                read::RowGroupDeserializer::new(column_chunks, row_group.num_rows(), remaining)
+            {
+                let df = DataFrame::try_from((chunk?, fields.as_slice()))?;
+                remaining = remaining.map(|n| n.saturating_sub(df.height()));
+                dfs.push(df);
+            }
+        }
+
+        let df = if dfs.is_empty() {
+            DataFrame::new_no_checks(
+                fields
+                    .iter()
+                    .map(|f| Series::new_empty(&f.name, &DataType::from(&f.data_type)))
+                    .collect(),
+            )
+        } else {
+            accumulate_dataframes_vertical(dfs)?
+        };
+
+        match scan_opts.predicate {
+            Some(predicate) => {
+                let df = df.lazy().filter(predicate).collect()?;
+                df.select(&columns[..n_projected])
+            }
+            None => Ok(df),
+        }
+    }
+
+    fn schema(&self, _infer_schema_length: Option<usize>) -> PolarsResult<Schema> {
+        Ok(self.polars_schema())
+    }
+
+    fn allows_predicate_pushdown(&self) -> bool {
+        true
+    }
+
+    fn allows_projection_pushdown(&self) -> bool {
+        true
+    }
+
+    fn allows_slice_pushdown(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::range::RangeSource;
+    use crate::io::run_serving_reads;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    type Reads = Rc<RefCell<Vec<(u64, usize)>>>;
+
+    /// An in-memory stand-in for HTTP range requests, recording the ranges it serves.
+    struct MemorySource {
+        bytes: Vec<u8>,
+        reads: Reads,
+    }
+
+    impl RangeSource for MemorySource {
+        fn size(&self) -> std::io::Result<u64> {
+            Ok(self.bytes.len() as u64)
+        }
+
+        fn read_range(&self, offset: u64, length: usize) -> std::io::Result<Vec<u8>> {
+            self.reads.borrow_mut().push((offset, length));
+            let start = offset as usize;
+            Ok(self.bytes[start..start + length].to_vec())
+        }
+    }
+
+    fn csv_options() -> CsvScanOptions {
+        CsvScanOptions {
+            has_header: true,
+            ignore_errors: false,
+            skip_rows: 0,
+            parse_dates: false,
+            delimiter: b',',
+            quote_char: Some(b'"'),
+            comment_char: None,
+            eol_char: b'\n',
+            null_values: None,
+            overwrite_dtype: None,
+            encoding: CsvEncoding::Utf8,
+        }
+    }
+
+    #[test]
+    fn test_line_chunks() {
+        let buf = b"a,b\n1,\"x\ny\"\n2,z\n3,w";
+        let chunks = line_chunks(buf, Some(b'"'), b'\n', 1, 4);
+        assert_eq!(chunks, vec![4..12, 12..16, 16..19]);
+        assert_eq!(line_chunks(buf, Some(b'"'), b'\n', 1, 1024), vec![4..19]);
+        assert!(line_chunks(b"a,b\n", Some(b'"'), b'\n', 1, 4).is_empty());
+    }
+
+    #[test]
+    fn test_scan_csv_predicate() {
+        let buf = b"a,b,c\n1,x,true\n2,y,false\n3,z,true\n".to_vec();
+        let scan = CsvBytesScan::new(buf, csv_options());
+        let args = ScanArgsAnonymous {
+            fmt_str: "CSV BYTES",
+            ..Default::default()
+        };
+        let out = LazyFrame::anonymous_scan(Arc::new(scan), args)
+            .unwrap()
+            .filter(col("a").gt(lit(1)))
+            .select([col("b")])
+            .collect()
+            .unwrap();
+        assert!(out.frame_equal(&df!["b" => ["y", "z"]].unwrap()));
+    }
+
+    fn parquet_bytes(df: &mut DataFrame) -> Vec<u8> {
+        let mut bytes = vec![];
+        ParquetWriter::new(&mut bytes)
+            .with_statistics(true)
+            .with_row_group_size(Some(2))
+            .finish(df)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_scan_parquet_source() {
+        let mut df = df![
+            "year" => [2019, 2020, 2021, 2022],
+            "sales" => [1.0, 2.0, 3.0, 4.0],
+            "region" => ["a", "b", "c", "d"],
+        ]
+        .unwrap();
+        let bytes = parquet_bytes(&mut df);
+        let metadata = read::read_metadata(&mut Cursor::new(&bytes)).unwrap();
+        let reads = Reads::default();
+        let source = MemorySource {
+            bytes,
+            reads: reads.clone(),
+        };
+
+        let scan = ParquetRangeScan::try_new(RangeReader::try_new(source).unwrap()).unwrap();
+        let args = ScanArgsAnonymous {
+            schema: Some(scan.polars_schema()),
+            fmt_str: "PARQUET RANGES",
+            ..Default::default()
+        };
+        let lf = LazyFrame::anonymous_scan(Arc::new(scan), args)
+            .unwrap()
+            .filter(col("year").gt(lit(2020)))
+            .select([col("sales")]);
+        // the query runs on the pool, the reads are answered by this thread
+        let out = run_serving_reads(move || lf.collect()).unwrap();
+        assert!(out.frame_equal(&df!["sales" => [3.0, 4.0]].unwrap()));
+
+        // the first row group only holds years up to 2020, `region` is never needed
+        let skipped = metadata.row_groups[0]
+            .columns()
+            .iter()
+            .chain([&metadata.row_groups[1].columns()[2]]);
+        for column in skipped {
+            let (start, len) = column.byte_range();
+            for &(offset, length) in reads.borrow().iter() {
+                assert!(offset + length as u64 <= start || offset >= start + len);
+            }
+        }
+    }
+}
@@ -2,6 +2,7 @@ mod conversion;
 mod dataframe;
 mod datatypes;
 mod error;
+mod groupby;
 mod series;
 mod utils;
 mod lazy;
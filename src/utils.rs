@@ -1,28 +1,81 @@
-// use polars::prelude::{DataType, TimeUnit};
-// use wasm_bindgen::convert::FromWasmAbi;
+use polars::prelude::{DataType, TimeUnit};
+use wasm_bindgen::JsValue;
 
-// use crate::{JsResult, lazy::expr::JsExpr};
+use crate::{error::JsPolarsErr, JsResult};
 
-// pub fn str_to_polarstype(s: &str) -> DataType {
-//     match s {
-//         "UInt8" => DataType::UInt8,
-//         "UInt16" => DataType::UInt16,
-//         "UInt32" => DataType::UInt32,
-//         "UInt64" => DataType::UInt64,
-//         "Int8" => DataType::Int8,
-//         "Int16" => DataType::Int16,
-//         "Int32" => DataType::Int32,
-//         "Int64" => DataType::Int64,
-//         "Float32" => DataType::Float32,
-//         "Float64" => DataType::Float64,
-//         "Boolean" => DataType::Boolean,
-//         "Utf8" => DataType::Utf8,
-//         "Date" => DataType::Date,
-//         "Datetime" => DataType::Datetime(TimeUnit::Milliseconds, None),
-//         "Duration" => DataType::Duration(TimeUnit::Milliseconds),
-//         "Time" => DataType::Time,
-//         "List" => DataType::List(DataType::Null.into()),
-//         "Categorical" => DataType::Categorical(None),
-//         tp => panic!("Type {} not implemented in str_to_polarstype", tp),
-//     }
-// }
+pub fn str_to_polarstype(s: &str) -> Result<DataType, JsPolarsErr> {
+    let dtype = match s {
+        "UInt8" => DataType::UInt8,
+        "UInt16" => DataType::UInt16,
+        "UInt32" => DataType::UInt32,
+        "UInt64" => DataType::UInt64,
+        "Int8" => DataType::Int8,
+        "Int16" => DataType::Int16,
+        "Int32" => DataType::Int32,
+        "Int64" => DataType::Int64,
+        "Float32" => DataType::Float32,
+        "Float64" => DataType::Float64,
+        "Bool" | "Boolean" => DataType::Boolean,
+        "Utf8" => DataType::Utf8,
+        "Date" => DataType::Date,
+        "Datetime" => DataType::Datetime(TimeUnit::Milliseconds, None),
+        "Duration" => DataType::Duration(TimeUnit::Milliseconds),
+        "Time" => DataType::Time,
+        "List" => DataType::List(DataType::Null.into()),
+        "Categorical" => DataType::Categorical(None),
+        tp => {
+            return Err(JsPolarsErr::Other(format!(
+                "Type {} not implemented in str_to_polarstype",
+                tp
+            )))
+        }
+    };
+    Ok(dtype)
+}
+
+/// The strings of `arr`, erroring on anything that isn't a string.
+pub(crate) fn js_to_column_names(arr: &js_sys::Array, arg: &str) -> JsResult<Vec<String>> {
+    arr.iter()
+        .map(|v| {
+            v.as_string().ok_or_else(|| {
+                JsPolarsErr::Other(format!("{} should only contain column names", arg)).into()
+            })
+        })
+        .collect()
+}
+
+/// The field `key` of an options object, `None` when the object or the field isn't set.
+fn js_option(options: &JsValue, key: &str) -> JsResult<Option<JsValue>> {
+    if options.is_null() || options.is_undefined() {
+        return Ok(None);
+    }
+    let value = js_sys::Reflect::get(options, &key.into())?;
+    Ok((!value.is_null() && !value.is_undefined()).then_some(value))
+}
+
+pub(crate) fn js_option_string(options: &JsValue, key: &str) -> JsResult<Option<String>> {
+    js_option(options, key)?
+        .map(|v| {
+            v.as_string()
+                .ok_or_else(|| JsPolarsErr::Other(format!("{} must be a string", key)).into())
+        })
+        .transpose()
+}
+
+pub(crate) fn js_option_bool(options: &JsValue, key: &str) -> JsResult<Option<bool>> {
+    js_option(options, key)?
+        .map(|v| {
+            v.as_bool()
+                .ok_or_else(|| JsPolarsErr::Other(format!("{} must be a boolean", key)).into())
+        })
+        .transpose()
+}
+
+pub(crate) fn js_option_usize(options: &JsValue, key: &str) -> JsResult<Option<usize>> {
+    js_option(options, key)?
+        .map(|v| match v.as_f64() {
+            Some(n) if n >= 0.0 && n.fract() == 0.0 => Ok(n as usize),
+            _ => Err(JsPolarsErr::Other(format!("{} must be a non-negative integer", key)).into()),
+        })
+        .transpose()
+}